@@ -0,0 +1,173 @@
+use async_trait::async_trait;
+use reqwest::{header::IF_MATCH, RequestBuilder, StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    backend::TicketBackend,
+    error::{JotviewError, Result},
+    model::{Jotform, JotformPatch, NewJotform, Note, Status},
+};
+
+/// HTTP client for the jotview REST server.
+#[derive(Debug, Clone)]
+pub struct RestClient {
+    http: reqwest::Client,
+    base_url: Url,
+}
+
+impl RestClient {
+    /// `base_url` must end in `/`, as produced by [`crate::config::Config`].
+    pub fn new(base_url: Url) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url,
+        }
+    }
+
+    pub fn jotforms_url(&self) -> Url {
+        self.endpoint("jotforms")
+    }
+
+    fn endpoint(&self, path: &str) -> Url {
+        self.base_url
+            .join(path)
+            .expect("endpoint paths are valid relative URLs")
+    }
+
+    /// Sends an update and returns the stored ticket if the backend answers
+    /// with one. A version mismatch becomes [`JotviewError::Conflict`] with
+    /// the backend's current copy: from the response body, or else looked up
+    /// in a fresh list.
+    async fn send_update(
+        &self,
+        id: &str,
+        request: RequestBuilder,
+        url: &Url,
+    ) -> Result<Option<Jotform>> {
+        match send(request, url).await {
+            Ok(body) => Ok(serde_json::from_str(&body).ok()),
+            Err(JotviewError::HttpStatus { status, body, .. })
+                if status == StatusCode::CONFLICT || status == StatusCode::PRECONDITION_FAILED =>
+            {
+                let current = match serde_json::from_str::<Jotform>(&body) {
+                    Ok(current) => Some(current),
+                    Err(_) => self.get(id).await.ok(),
+                };
+                Err(JotviewError::Conflict {
+                    url: url.to_string(),
+                    current: current.map(Box::new),
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[async_trait]
+impl TicketBackend for RestClient {
+    fn location(&self) -> String {
+        self.jotforms_url().to_string()
+    }
+
+    async fn list(&self) -> Result<Vec<Jotform>> {
+        let url = self.jotforms_url();
+        let body = send(self.http.get(url.clone()), &url).await?;
+        decode(&body, &url)
+    }
+
+    /// The server has no single-ticket endpoint, so this picks it out of
+    /// the list.
+    async fn get(&self, id: &str) -> Result<Jotform> {
+        self.list()
+            .await?
+            .into_iter()
+            .find(|j| j.id == id)
+            .ok_or_else(|| JotviewError::NotFound { id: id.to_string() })
+    }
+
+    async fn create(&self, jotform: &NewJotform) -> Result<Jotform> {
+        let url = self.jotforms_url();
+        let body = send(self.http.post(url.clone()).json(jotform), &url).await?;
+        decode(&body, &url)
+    }
+
+    /// Sent with the revision in `If-Match`.
+    async fn update_status(
+        &self,
+        id: &str,
+        status: &Status,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>> {
+        let url = self.endpoint(&format!("jotforms/{}/status", id));
+        let request = self
+            .http
+            .post(url.clone())
+            .json(&serde_json::json!({ "new_status": status }));
+        self.send_update(id, if_match(request, version), &url).await
+    }
+
+    /// Sent as a `PATCH` holding only the fields set in `patch`.
+    async fn update_fields(
+        &self,
+        id: &str,
+        patch: &JotformPatch,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>> {
+        let url = self.endpoint(&format!("jotforms/{}", id));
+        let request = self.http.patch(url.clone()).json(patch);
+        self.send_update(id, if_match(request, version), &url).await
+    }
+
+    async fn notes(&self, id: &str) -> Result<Vec<Note>> {
+        let url = self.endpoint(&format!("jotforms/{}/notes", id));
+        let body = send(self.http.get(url.clone()), &url).await?;
+        decode(&body, &url)
+    }
+
+    async fn add_note(&self, id: &str, note: &Note) -> Result<Note> {
+        let url = self.endpoint(&format!("jotforms/{}/notes", id));
+        let body = send(self.http.post(url.clone()).json(note), &url).await?;
+        decode(&body, &url)
+    }
+
+    async fn staff(&self) -> Result<Vec<String>> {
+        let url = self.endpoint("staff");
+        let body = send(self.http.get(url.clone()), &url).await?;
+        decode(&body, &url)
+    }
+}
+
+/// Makes the request conditional on the ticket still being at `version`.
+fn if_match(request: RequestBuilder, version: Option<&str>) -> RequestBuilder {
+    match version {
+        Some(version) => request.header(IF_MATCH, format!("\"{}\"", version)),
+        None => request,
+    }
+}
+
+/// Sends a request and returns the body of a successful response.
+pub async fn send(request: RequestBuilder, url: &Url) -> Result<String> {
+    let network = |source| JotviewError::Network {
+        url: url.to_string(),
+        source,
+    };
+
+    let response = request.send().await.map_err(network)?;
+    let status = response.status();
+    let body = response.text().await.map_err(network)?;
+    if !status.is_success() {
+        return Err(JotviewError::HttpStatus {
+            url: url.to_string(),
+            status,
+            body,
+        });
+    }
+    Ok(body)
+}
+
+pub fn decode<T: DeserializeOwned>(body: &str, url: &Url) -> Result<T> {
+    serde_json::from_str(body).map_err(|source| JotviewError::Decode {
+        url: url.to_string(),
+        source,
+    })
+}
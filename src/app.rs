@@ -0,0 +1,2014 @@
+use chrono::{DateTime, Local};
+use crossterm::{
+    event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{enable_raw_mode, EnterAlternateScreen},
+};
+use futures::StreamExt;
+use ratatui::{widgets::ScrollbarState, DefaultTerminal, Frame};
+use std::{
+    cell::Cell,
+    collections::{HashMap, HashSet, VecDeque},
+    env, fs, io,
+    path::Path,
+    process,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    time::{self, Instant, Interval, MissedTickBehavior},
+};
+
+use crate::{
+    assignee::AssigneePicker,
+    backend::{self, TicketBackend},
+    cache::{Cache, Snapshot},
+    command::Command,
+    config::{self, Config},
+    conflict::{Conflict, Resolution},
+    edit_form::EditForm,
+    error::JotviewError,
+    export::{self, ExportPrompt},
+    filter::{self, DateRange, Filter, FilterItem, FilterPanel},
+    model::{Jotform, JotformPatch, Note, Status},
+    new_ticket::{NewTicketForm, TicketField},
+    notes::{DetailPane, NoteInput, NoteThread},
+    outbox::{Change, Outbox, QueuedChange},
+    report::{self, Report},
+    search::Search,
+    sort::SortOrder,
+    startup::{ConnectionFailure, Startup},
+    status_menu::{self, StatusMenu},
+};
+
+/// How often the spinner advances while background work is in flight.
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Result of a background network task, delivered back to the event loop.
+#[derive(Debug)]
+pub enum Message {
+    /// The first fetch at startup, or a retry of it.
+    Loaded(Result<Vec<Jotform>, JotviewError>),
+    Refreshed(Result<Vec<Jotform>, JotviewError>),
+    /// A save finished; `Ok` holds the ticket as stored if the backend sent
+    /// it back.
+    StatusSaved {
+        status: Status,
+        /// The row before the optimistic change, for rollback and conflicts.
+        base: Jotform,
+        result: Result<Option<Jotform>, JotviewError>,
+    },
+    FieldsSaved {
+        patch: JotformPatch,
+        base: Jotform,
+        result: Result<Option<Jotform>, JotviewError>,
+    },
+    Created(Result<Jotform, JotviewError>),
+    NotesLoaded {
+        id: String,
+        result: Result<Vec<Note>, JotviewError>,
+    },
+    NoteAdded {
+        id: String,
+        result: Result<Note, JotviewError>,
+    },
+    StaffLoaded(Result<Vec<String>, JotviewError>),
+    /// Outcome of sending each queued offline change, in queue order.
+    Replayed(Vec<(QueuedChange, Result<Option<Jotform>, JotviewError>)>),
+    /// The changes chosen in the conflict dialog were sent.
+    Resolved {
+        conflict: Box<Conflict>,
+        resolution: Resolution,
+        result: Result<Option<Jotform>, JotviewError>,
+    },
+}
+
+/// A dismissible error shown in a popup over the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorPopup {
+    pub title: String,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub struct App {
+    pub config: Config,
+    pub backend: Arc<dyn TicketBackend>,
+    pub cache: Option<Cache>,
+    pub startup: Startup,
+    /// Set while showing cached jotforms because the backend is unreachable;
+    /// holds when the cache was written.
+    pub offline_since: Option<DateTime<Local>>,
+    /// When to next try reaching the backend while offline, and how many
+    /// tries have failed so far.
+    pub reconnect_at: Option<Instant>,
+    pub reconnect_attempt: u32,
+    /// Changes made while offline, sent once the backend is back.
+    pub outbox: Outbox,
+    /// Whether queued changes are being sent right now.
+    pub syncing: bool,
+    pub jotforms: Vec<Jotform>,
+    pub selected_id: String,
+    pub scroll_state: ScrollbarState,
+    /// Scroll position of the description, in the bottom pane or detail view.
+    pub description_offset: u16,
+    /// Whether Enter has opened the full-screen detail view.
+    pub detail_open: bool,
+    /// Whether Tab has switched from the table to the dashboard.
+    pub dashboard_open: bool,
+    /// The detail view pane the scroll keys apply to.
+    pub detail_pane: DetailPane,
+    /// Work logs fetched so far, keyed by jotform id.
+    pub notes: HashMap<String, NoteThread>,
+    /// Scroll position of the notes thread in the detail view.
+    pub notes_offset: u16,
+    pub note_input: Option<NoteInput>,
+    /// First visible table row, written back by the renderer so the table
+    /// keeps its scroll position between frames.
+    pub table_offset: Cell<usize>,
+    pub search: Search,
+    pub filter: Filter,
+    pub filter_panel: FilterPanel,
+    pub sort: SortOrder,
+    pub status_menu: StatusMenu,
+    pub edit_form: Option<EditForm>,
+    pub assignee_picker: AssigneePicker,
+    /// Staff list from the backend, fetched the first time the picker opens
+    /// unless the config file lists it.
+    pub staff: Option<Vec<String>>,
+    pub new_ticket: Option<NewTicketForm>,
+    pub export_prompt: Option<ExportPrompt>,
+    /// A one-off confirmation for the status bar, cleared by the next key.
+    pub notice: Option<String>,
+    pub refreshing: bool,
+    pub last_refreshed: Option<DateTime<Local>>,
+    /// Status saves still in flight, keyed by jotform id.
+    pub pending_saves: HashMap<String, Status>,
+    /// Field edits still in flight, keyed by jotform id.
+    pub pending_edits: HashMap<String, JotformPatch>,
+    /// Saves someone else beat us to, waiting for the user to pick a side.
+    pub conflicts: VecDeque<Conflict>,
+    /// Tickets whose conflict resolution is being sent.
+    pub resolving: HashSet<String>,
+    /// Errors waiting to be acknowledged, oldest first.
+    pub errors: VecDeque<ErrorPopup>,
+    pub spinner_frame: usize,
+    /// Set by `c` on the connection error screen; handled by the run loop,
+    /// which owns the terminal.
+    edit_config_requested: bool,
+    /// Backend work asked for since the run loop last looked.
+    commands: Vec<Command>,
+    messages: UnboundedSender<Message>,
+    inbox: Option<UnboundedReceiver<Message>>,
+    pub exit: bool,
+}
+
+impl App {
+    pub fn new(config: Config, backend: Arc<dyn TicketBackend>) -> Self {
+        let (messages, inbox) = mpsc::unbounded_channel();
+        Self {
+            cache: config
+                .source_url()
+                .and_then(|url| Cache::for_base_url(&url)),
+            config,
+            backend,
+            startup: Startup::Connecting {
+                attempt: 1,
+                auto_retry: false,
+            },
+            offline_since: None,
+            reconnect_at: None,
+            reconnect_attempt: 0,
+            outbox: Outbox::default(),
+            syncing: false,
+            jotforms: Vec::new(),
+            selected_id: String::new(),
+            scroll_state: ScrollbarState::default(),
+            description_offset: 0,
+            detail_open: false,
+            dashboard_open: false,
+            detail_pane: DetailPane::default(),
+            notes: HashMap::new(),
+            notes_offset: 0,
+            note_input: None,
+            table_offset: Cell::new(0),
+            search: Search::default(),
+            filter: Filter::default(),
+            filter_panel: FilterPanel::default(),
+            sort: SortOrder::default(),
+            status_menu: StatusMenu::default(),
+            edit_form: None,
+            assignee_picker: AssigneePicker::default(),
+            staff: None,
+            new_ticket: None,
+            export_prompt: None,
+            notice: None,
+            refreshing: false,
+            last_refreshed: None,
+            pending_saves: HashMap::new(),
+            pending_edits: HashMap::new(),
+            conflicts: VecDeque::new(),
+            resolving: HashSet::new(),
+            errors: VecDeque::new(),
+            spinner_frame: 0,
+            edit_config_requested: false,
+            commands: Vec::new(),
+            messages,
+            inbox: Some(inbox),
+            exit: false,
+        }
+    }
+
+    pub async fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        let mut inbox = self.inbox.take().expect("App::run called twice");
+        let mut events = EventStream::new();
+        let mut refresh = refresh_timer(self.config.refresh_interval);
+        let mut spinner = time::interval(SPINNER_INTERVAL);
+
+        self.load_outbox();
+        self.start_initial_load();
+
+        while !self.exit {
+            for command in self.take_commands() {
+                self.execute(command);
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let retry_at = match &self.startup {
+                Startup::Failed(failure) => failure.retry_at,
+                _ => None,
+            };
+            tokio::select! {
+                event = events.next() => match event {
+                    Some(Ok(event)) => self.handle_event(event),
+                    Some(Err(e)) => return Err(e),
+                    None => break,
+                },
+                Some(message) = inbox.recv() => self.handle_message(message),
+                _ = next_tick(&mut refresh), if self.startup.is_done() => self.start_refresh(),
+                _ = sleep_until(retry_at) => self.start_initial_load(),
+                _ = sleep_until(self.reconnect_at) => {
+                    self.reconnect_at = None;
+                    self.start_refresh();
+                }
+                // Also keeps the retry countdown on screen up to date.
+                _ = spinner.tick(), if self.is_busy() || retry_at.is_some() => {
+                    self.spinner_frame = self.spinner_frame.wrapping_add(1);
+                }
+            }
+
+            if self.edit_config_requested {
+                self.edit_config_requested = false;
+                // The event stream reads stdin in the background, so it must
+                // not be alive while the editor owns the terminal.
+                drop(events);
+                self.edit_config(terminal)?;
+                events = EventStream::new();
+                refresh = refresh_timer(self.config.refresh_interval);
+            }
+        }
+        Ok(())
+    }
+
+    /// The failure the user quit from, if they gave up on the connection
+    /// error screen.
+    pub fn unresolved_failure(&self) -> Option<&ConnectionFailure> {
+        match &self.startup {
+            Startup::Failed(failure) if self.exit => Some(failure),
+            _ => None,
+        }
+    }
+
+    /// Backend work the handlers have asked for since the last call, oldest
+    /// first.
+    pub fn take_commands(&mut self) -> Vec<Command> {
+        std::mem::take(&mut self.commands)
+    }
+
+    /// Runs `command` in the background; its outcome arrives in the inbox.
+    fn execute(&self, command: Command) {
+        let backend = self.backend.clone();
+        let messages = self.messages.clone();
+        tokio::spawn(async move {
+            let _ = messages.send(command.run(backend.as_ref()).await);
+        });
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        frame.render_widget(self, frame.area());
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        if let Event::Key(key_event) = event {
+            if key_event.kind == KeyEventKind::Press {
+                self.handle_key_events(key_event);
+            }
+        }
+    }
+
+    pub fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::Loaded(Ok(jotforms)) => {
+                self.finish_initial_load(jotforms);
+                self.save_cache();
+                if !self.outbox.is_empty() {
+                    // Queued last session; the fresh list is the server copy
+                    // to check them against.
+                    self.replay_outbox();
+                }
+            }
+            Message::Loaded(Err(e)) => self.fail_initial_load(e),
+            Message::Refreshed(Ok(jotforms)) => {
+                self.refreshing = false;
+                self.offline_since = None;
+                self.reconnect_at = None;
+                self.reconnect_attempt = 0;
+                self.last_refreshed = Some(Local::now());
+                self.merge_refreshed(jotforms);
+                self.save_cache();
+                if !self.outbox.is_empty() && !self.syncing {
+                    self.replay_outbox();
+                }
+            }
+            // Offline, a failed reconnect attempt is expected; stay quiet.
+            Message::Refreshed(Err(_)) if self.offline_since.is_some() => {
+                self.refreshing = false;
+                self.schedule_reconnect();
+            }
+            Message::Refreshed(Err(e)) => {
+                self.refreshing = false;
+                self.show_error("Refresh failed", e.to_string());
+            }
+            Message::StatusSaved {
+                status,
+                base,
+                result,
+            } => {
+                // Only the latest save for a row clears its pending marker.
+                if self.pending_saves.get(&base.id) == Some(&status) {
+                    self.pending_saves.remove(&base.id);
+                }
+                match result {
+                    Ok(stored) => {
+                        if let Some(stored) = stored {
+                            self.accept_stored(stored);
+                        }
+                    }
+                    Err(JotviewError::Conflict {
+                        current: Some(current),
+                        ..
+                    }) => {
+                        let conflict = Conflict::new(base, &Change::Status { status }, *current);
+                        self.add_conflict(conflict);
+                    }
+                    Err(e) => self.roll_back_status(&base.id, &status, base.status, e),
+                }
+            }
+            Message::FieldsSaved {
+                patch,
+                base,
+                result,
+            } => {
+                let id = base.id.clone();
+                if self.pending_edits.get(&id) == Some(&patch) {
+                    self.pending_edits.remove(&id);
+                }
+                match result {
+                    Ok(Some(stored)) => self.accept_stored(stored),
+                    Ok(None) => {
+                        if let Some(jotform) = self.jotforms.iter_mut().find(|j| j.id == id) {
+                            patch.apply_to(jotform);
+                        }
+                        self.sort.apply(&mut self.jotforms);
+                        self.ensure_selection_visible();
+                        self.save_cache();
+                    }
+                    Err(JotviewError::Conflict {
+                        current: Some(current),
+                        ..
+                    }) => {
+                        let conflict = Conflict::new(base, &Change::Fields { patch }, *current);
+                        self.add_conflict(conflict);
+                    }
+                    Err(e) => {
+                        // A refresh while the save was in flight showed it.
+                        if let Some(jotform) = self.jotforms.iter_mut().find(|j| j.id == id) {
+                            patch.undo(&base).apply_to(jotform);
+                        }
+                        self.sort.apply(&mut self.jotforms);
+                        self.show_error(
+                            format!("Could not save changes to ticket {}", id),
+                            format!("{}\n\nThe ticket was left unchanged.", e),
+                        );
+                    }
+                }
+            }
+            Message::Created(result) => match result {
+                Ok(jotform) => {
+                    self.new_ticket = None;
+                    let id = jotform.id.clone();
+                    self.sort.insert(&mut self.jotforms, jotform);
+                    if self.visible_jotforms().iter().any(|j| j.id == id) {
+                        self.selected_id = id;
+                        self.description_offset = 0;
+                    }
+                    self.save_cache();
+                }
+                Err(e) => {
+                    if let Some(form) = &mut self.new_ticket {
+                        form.saving = false;
+                    }
+                    self.show_error("Could not create the ticket", e.to_string());
+                }
+            },
+            Message::NotesLoaded { id, result } => {
+                let thread = self.notes.entry(id).or_default();
+                thread.loading = false;
+                match result {
+                    Ok(notes) => {
+                        thread.notes = notes;
+                        thread.error = None;
+                    }
+                    Err(e) => thread.error = Some(e.to_string()),
+                }
+            }
+            Message::NoteAdded { id, result } => match result {
+                Ok(note) => {
+                    if self.note_input.as_ref().is_some_and(|input| input.id == id) {
+                        self.note_input = None;
+                    }
+                    self.notes.entry(id).or_default().notes.push(note);
+                }
+                Err(e) => {
+                    if let Some(input) = &mut self.note_input {
+                        input.saving = false;
+                    }
+                    self.show_error(
+                        format!("Could not add a note to ticket {}", id),
+                        e.to_string(),
+                    );
+                }
+            },
+            Message::StaffLoaded(Ok(staff)) => {
+                if self.assignee_picker.open {
+                    self.fill_assignee_picker(&staff);
+                }
+                self.staff = Some(staff);
+            }
+            Message::StaffLoaded(Err(e)) => {
+                self.assignee_picker.close();
+                self.show_error(
+                    "Could not load the staff list",
+                    format!(
+                        "{}\n\nList names under `staff` in the config file to skip the lookup.",
+                        e
+                    ),
+                );
+            }
+            Message::Replayed(results) => self.finish_replay(results),
+            Message::Resolved {
+                conflict,
+                resolution,
+                result,
+            } => {
+                self.resolving.remove(conflict.id());
+                match result {
+                    Ok(stored) => {
+                        let stored = stored.unwrap_or_else(|| conflict.resolved(resolution));
+                        self.accept_stored(stored);
+                    }
+                    // Beaten again; start over from the newer server copy.
+                    Err(JotviewError::Conflict {
+                        current: Some(current),
+                        ..
+                    }) => self.add_conflict(Conflict {
+                        base: conflict.theirs.clone(),
+                        mine: conflict.resolved(resolution),
+                        theirs: *current,
+                    }),
+                    Err(e) => {
+                        let id = conflict.id().to_string();
+                        self.accept_stored(conflict.theirs);
+                        self.show_error(
+                            format!("Could not save changes to ticket {}", id),
+                            format!("{}\n\nThe ticket shows the server copy.", e),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn show_error(&mut self, title: impl Into<String>, message: impl Into<String>) {
+        self.errors.push_back(ErrorPopup {
+            title: title.into(),
+            message: message.into(),
+        });
+    }
+
+    /// Undoes an optimistic status change whose save failed, unless the row
+    /// has been changed again since.
+    fn roll_back_status(
+        &mut self,
+        id: &str,
+        failed: &Status,
+        previous: Status,
+        error: JotviewError,
+    ) {
+        let mut message = error.to_string();
+        if let Some(jotform) = self.jotforms.iter_mut().find(|j| j.id == id) {
+            if jotform.status == *failed {
+                message.push_str(&format!(
+                    "\n\nThe status of {}'s ticket was reverted to {}.",
+                    jotform.submitter_name.first, previous
+                ));
+                jotform.status = previous;
+                self.sort.apply(&mut self.jotforms);
+                self.ensure_selection_visible();
+            }
+        }
+        self.show_error(format!("Could not set status to {}", failed), message);
+    }
+
+    /// Puts the ticket as the backend stored it in place of the local row,
+    /// keeping newer changes that are still being saved.
+    fn accept_stored(&mut self, mut stored: Jotform) {
+        self.reapply_pending(&mut stored);
+        let Some(jotform) = self.jotforms.iter_mut().find(|j| j.id == stored.id) else {
+            return;
+        };
+        *jotform = stored;
+        self.sort.apply(&mut self.jotforms);
+        self.ensure_selection_visible();
+        self.save_cache();
+    }
+
+    /// Asks the user to settle a conflict, unless the other side only
+    /// changed fields ours leaves alone; then ours is simply sent again.
+    fn add_conflict(&mut self, conflict: Conflict) {
+        if conflict.overlaps() {
+            self.conflicts.push_back(conflict);
+        } else {
+            self.start_resolution(conflict, Resolution::Reapply);
+        }
+    }
+
+    /// Settles the conflict at the front of the queue.
+    fn resolve_conflict(&mut self, resolution: Resolution) {
+        if let Some(conflict) = self.conflicts.pop_front() {
+            self.start_resolution(conflict, resolution);
+        }
+    }
+
+    /// Shows the outcome of `resolution` right away and sends what it takes
+    /// to get there.
+    fn start_resolution(&mut self, conflict: Conflict, resolution: Resolution) {
+        if conflict.changes(resolution).is_empty() {
+            self.accept_stored(conflict.theirs);
+            return;
+        }
+        if let Some(jotform) = self.jotforms.iter_mut().find(|j| j.id == conflict.id()) {
+            *jotform = conflict.resolved(resolution);
+        }
+        self.sort.apply(&mut self.jotforms);
+        self.ensure_selection_visible();
+        self.resolving.insert(conflict.id().to_string());
+        self.commands.push(Command::Resolve {
+            conflict: Box::new(conflict),
+            resolution,
+        });
+    }
+
+    /// Whether any change is on its way to the backend.
+    pub fn is_saving(&self) -> bool {
+        !self.pending_saves.is_empty()
+            || !self.pending_edits.is_empty()
+            || self.new_ticket.as_ref().is_some_and(|form| form.saving)
+            || self.note_input.as_ref().is_some_and(|input| input.saving)
+            || self.syncing
+            || !self.resolving.is_empty()
+    }
+
+    /// Whether any background request is in flight.
+    pub fn is_busy(&self) -> bool {
+        self.refreshing
+            || self.is_saving()
+            || self.notes.values().any(|thread| thread.loading)
+            || matches!(self.startup, Startup::Connecting { .. })
+    }
+
+    /// Fetches the list for the first time, or retries after a failure.
+    pub fn start_initial_load(&mut self) {
+        let (attempt, auto_retry) = match &self.startup {
+            Startup::Failed(failure) => (failure.attempt + 1, failure.retry_at.is_some()),
+            Startup::Connecting {
+                attempt,
+                auto_retry,
+            } => (*attempt, *auto_retry),
+            Startup::Done => return,
+        };
+        self.startup = Startup::Connecting {
+            attempt,
+            auto_retry,
+        };
+        self.commands.push(Command::Load);
+    }
+
+    fn finish_initial_load(&mut self, jotforms: Vec<Jotform>) {
+        self.jotforms = jotforms;
+        self.sort.apply(&mut self.jotforms);
+        if let Some(first_jotform) = self.jotforms.first() {
+            self.selected_id = first_jotform.id.clone();
+        }
+        self.startup = Startup::Done;
+        self.last_refreshed = Some(Local::now());
+    }
+
+    fn fail_initial_load(&mut self, error: JotviewError) {
+        let Startup::Connecting {
+            attempt,
+            auto_retry,
+        } = self.startup
+        else {
+            return;
+        };
+        let cached = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.load().ok().flatten());
+        // Once the user has asked to retry with `r`, they wait for the
+        // backend; `o` still opens the cached copy.
+        let cached = match cached {
+            Some(snapshot) if !auto_retry => {
+                self.go_offline(snapshot);
+                return;
+            }
+            cached => cached,
+        };
+        self.startup = Startup::Failed(ConnectionFailure {
+            url: self.backend.location(),
+            error: error.to_string(),
+            attempt,
+            retry_at: auto_retry.then(|| Instant::now() + ConnectionFailure::backoff(attempt)),
+            offline_copy: cached.map(|snapshot| (snapshot.saved_at, snapshot.jotforms.len())),
+        });
+    }
+
+    /// Shows the cached list instead of waiting for the backend.
+    fn start_offline(&mut self) {
+        let snapshot = match self.cache.as_ref().map(Cache::load) {
+            Some(Ok(Some(snapshot))) => snapshot,
+            Some(Ok(None)) | None => {
+                self.show_error(
+                    "No cached copy",
+                    "Nothing has been cached for this backend yet.",
+                );
+                return;
+            }
+            Some(Err(e)) => {
+                self.show_error("Could not read the offline copy", e.to_string());
+                return;
+            }
+        };
+        self.go_offline(snapshot);
+    }
+
+    /// Starts from a cached snapshot, with any queued changes on top, and
+    /// keeps trying to reach the backend in the background.
+    fn go_offline(&mut self, snapshot: Snapshot) {
+        let mut jotforms = snapshot.jotforms;
+        self.outbox.apply_to(&mut jotforms);
+        self.finish_initial_load(jotforms);
+        self.offline_since = Some(snapshot.saved_at);
+        self.last_refreshed = Some(snapshot.saved_at);
+        self.schedule_reconnect();
+    }
+
+    fn schedule_reconnect(&mut self) {
+        self.reconnect_attempt += 1;
+        self.reconnect_at =
+            Some(Instant::now() + ConnectionFailure::backoff(self.reconnect_attempt));
+    }
+
+    fn load_outbox(&mut self) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        match cache.load_outbox() {
+            Ok(outbox) => self.outbox = outbox,
+            Err(e) => {
+                let path = cache.outbox_path().display().to_string();
+                self.show_error(
+                    "Could not read queued offline changes",
+                    format!("{}: {}", path, e),
+                );
+            }
+        }
+    }
+
+    fn save_outbox(&mut self) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        if let Err(e) = cache.save_outbox(&self.outbox) {
+            let path = cache.outbox_path().display().to_string();
+            self.show_error(
+                "Could not save queued offline changes",
+                format!("{}: {}", path, e),
+            );
+        }
+    }
+
+    /// Queues a change made offline to the row that looks like `base`, and
+    /// shows it right away.
+    fn queue_offline(&mut self, base: Jotform, change: Change) {
+        if let Some(jotform) = self.jotforms.iter_mut().find(|j| j.id == base.id) {
+            change.apply_to(jotform);
+        }
+        self.outbox.queue(&base, change);
+        self.save_outbox();
+        self.sort.apply(&mut self.jotforms);
+        self.ensure_selection_visible();
+    }
+
+    /// Sends queued offline changes, checking them against the server copy
+    /// just loaded into `jotforms`. Changes to fields that someone else
+    /// changed meanwhile go to the conflict dialog instead, and changes to
+    /// deleted tickets are dropped and reported.
+    fn replay_outbox(&mut self) {
+        let mut deleted = Vec::new();
+        let mut replay = Vec::new();
+        for queued in self.outbox.changes.clone() {
+            let Some(server) = self.jotforms.iter().find(|j| j.id == queued.id) else {
+                deleted.push(queued);
+                continue;
+            };
+            let conflict = Conflict::new(queued.base.clone(), &queued.change, server.clone());
+            if conflict.overlaps() {
+                self.outbox.remove(&queued);
+                self.conflicts.push_back(conflict);
+            } else {
+                replay.push(queued);
+            }
+        }
+
+        if !deleted.is_empty() {
+            for queued in &deleted {
+                self.outbox.remove(queued);
+            }
+            let lines: Vec<String> = deleted
+                .iter()
+                .map(|queued| format!("• ticket {} was deleted on the server", queued.id))
+                .collect();
+            self.show_error("Offline changes not applied", lines.join("\n"));
+        }
+        self.save_outbox();
+        if replay.is_empty() {
+            return;
+        }
+        // Each change goes against the revision the server has now, or the
+        // one the previous change to the same ticket left.
+        let versions: HashMap<String, Option<String>> = self
+            .jotforms
+            .iter()
+            .map(|j| (j.id.clone(), j.updated_at.clone()))
+            .collect();
+        self.outbox.apply_to(&mut self.jotforms);
+        self.sort.apply(&mut self.jotforms);
+        self.syncing = true;
+        self.commands.push(Command::Replay {
+            changes: replay,
+            versions,
+        });
+    }
+
+    fn finish_replay(
+        &mut self,
+        results: Vec<(QueuedChange, Result<Option<Jotform>, JotviewError>)>,
+    ) {
+        self.syncing = false;
+        let mut rejected = Vec::new();
+        let mut unreachable = false;
+        for (queued, result) in results {
+            match result {
+                // The refresh below picks up what was stored.
+                Ok(_) => self.outbox.remove(&queued),
+                // Still queued; tried again on the next reconnect.
+                Err(JotviewError::Network { .. }) => unreachable = true,
+                Err(JotviewError::Conflict {
+                    current: Some(current),
+                    ..
+                }) => {
+                    self.outbox.remove(&queued);
+                    self.add_conflict(Conflict::new(queued.base, &queued.change, *current));
+                }
+                Err(e) => {
+                    self.outbox.remove(&queued);
+                    rejected.push(format!("• ticket {}: {}", queued.id, e));
+                }
+            }
+        }
+        self.save_outbox();
+        if !rejected.is_empty() {
+            self.show_error(
+                "Offline changes rejected",
+                format!(
+                    "{}\n\nThe server copy was kept for these tickets.",
+                    rejected.join("\n")
+                ),
+            );
+        }
+        if unreachable {
+            self.offline_since = self.last_refreshed.or(Some(Local::now()));
+            self.schedule_reconnect();
+        } else {
+            self.start_refresh();
+        }
+    }
+
+    fn save_cache(&mut self) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        if let Err(e) = cache.save(&self.jotforms) {
+            let path = cache.path().display().to_string();
+            self.show_error(
+                "Could not write the offline copy",
+                format!("{}: {}", path, e),
+            );
+        }
+    }
+
+    /// Opens the config file in `$VISUAL`/`$EDITOR`, then reloads it and
+    /// retries the connection with the new settings.
+    fn edit_config(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        let Some(path) = self.config.edit_path() else {
+            self.show_error("No config directory", "Pass --config to choose a file.");
+            return Ok(());
+        };
+        if !path.exists() {
+            let created = path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| fs::write(&path, config::TEMPLATE));
+            if let Err(e) = created {
+                self.show_error(
+                    "Could not create the config file",
+                    format!("{}: {}", path.display(), e),
+                );
+                return Ok(());
+            }
+        }
+
+        ratatui::restore();
+        let status = editor_command(&path).status();
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        terminal.clear()?;
+
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => {
+                self.show_error(
+                    "Editor failed",
+                    format!("The editor exited with {}.", status),
+                );
+                return Ok(());
+            }
+            Err(e) => {
+                self.show_error("Could not start an editor", e.to_string());
+                return Ok(());
+            }
+        }
+
+        match self.config.reload() {
+            Ok(config) => {
+                match backend::from_config(&config) {
+                    Ok(backend) => self.backend = backend,
+                    Err(e) => {
+                        self.show_error("Invalid config", e.to_string());
+                        return Ok(());
+                    }
+                }
+                self.cache = config
+                    .source_url()
+                    .and_then(|url| Cache::for_base_url(&url));
+                self.config = config;
+                self.start_initial_load();
+            }
+            Err(e) => self.show_error("Invalid config", e.to_string()),
+        }
+        Ok(())
+    }
+
+    /// Re-fetches `/jotforms` in the background unless a fetch is already running.
+    fn start_refresh(&mut self) {
+        if self.refreshing {
+            return;
+        }
+        self.refreshing = true;
+        self.commands.push(Command::Refresh);
+    }
+
+    /// Saves a status change to the row that looked like `base` in the
+    /// background; the row is updated immediately.
+    fn start_status_save(&mut self, base: Jotform, status: Status) {
+        if self.offline_since.is_some() {
+            self.queue_offline(base, Change::Status { status });
+            return;
+        }
+        self.pending_saves.insert(base.id.clone(), status.clone());
+        self.commands.push(Command::SaveStatus { base, status });
+    }
+
+    /// Sends changed fields in the background; the row is updated once the
+    /// backend accepts them.
+    fn start_fields_save(&mut self, id: String, patch: JotformPatch) {
+        let Some(base) = self.jotforms.iter().find(|j| j.id == id).cloned() else {
+            return;
+        };
+        if self.offline_since.is_some() {
+            self.queue_offline(base, Change::Fields { patch });
+            return;
+        }
+        self.pending_edits.insert(id, patch.clone());
+        self.commands.push(Command::SaveFields { base, patch });
+    }
+
+    /// POSTs the new-ticket form; it stays open until the backend answers.
+    fn start_create(&mut self) {
+        let Some(form) = &mut self.new_ticket else {
+            return;
+        };
+        if form.saving {
+            return;
+        }
+        match form.submission(Local::now().naive_local()) {
+            Err(e) => form.error = Some(e),
+            Ok(submission) => {
+                form.error = None;
+                form.saving = true;
+                self.commands.push(Command::Create(submission));
+            }
+        }
+    }
+
+    /// Fetches the selected jotform's work log in the background.
+    fn load_notes(&mut self) {
+        let id = self.selected_id.clone();
+        if id.is_empty() {
+            return;
+        }
+        let offline = self.offline_since.is_some();
+        let thread = self.notes.entry(id.clone()).or_default();
+        if offline {
+            thread.error = Some("Notes aren't available offline.".to_string());
+            return;
+        }
+        if thread.loading {
+            return;
+        }
+        thread.loading = true;
+        self.commands.push(Command::LoadNotes { id });
+    }
+
+    /// POSTs the note being written; the input stays open until it is stored.
+    fn start_add_note(&mut self) {
+        let Some(input) = &mut self.note_input else {
+            return;
+        };
+        if input.saving {
+            return;
+        }
+        match input.note(Local::now().naive_local()) {
+            Err(e) => input.error = Some(e),
+            Ok(note) => {
+                input.error = None;
+                input.saving = true;
+                let id = input.id.clone();
+                self.commands.push(Command::AddNote { id, note });
+            }
+        }
+    }
+
+    /// Replaces the loaded jotforms with a fresh copy from the backend.
+    ///
+    /// Rows with a save still in flight keep their local status, and the
+    /// selection stays on the same jotform (or the row that took its place if
+    /// it was removed). The table scroll offset is left alone.
+    fn merge_refreshed(&mut self, mut jotforms: Vec<Jotform>) {
+        let previous_index = self
+            .visible_jotforms()
+            .iter()
+            .position(|j| j.id == self.selected_id);
+
+        for jotform in &mut jotforms {
+            self.reapply_pending(jotform);
+        }
+        if self.syncing {
+            self.outbox.apply_to(&mut jotforms);
+        }
+        self.sort.apply(&mut jotforms);
+        self.jotforms = jotforms;
+
+        let visible = self.visible_jotforms();
+        if visible.iter().any(|j| j.id == self.selected_id) {
+            return;
+        }
+        let replacement = previous_index
+            .and_then(|index| visible.get(index.min(visible.len().saturating_sub(1))))
+            .or(visible.first())
+            .map(|j| j.id.clone())
+            .unwrap_or_default();
+        self.selected_id = replacement;
+        self.description_offset = 0;
+    }
+
+    /// Lays status and field changes still in flight over a copy of the
+    /// ticket from the backend, so a refresh doesn't show them undone.
+    fn reapply_pending(&self, jotform: &mut Jotform) {
+        if let Some(status) = self.pending_saves.get(&jotform.id) {
+            jotform.status = status.clone();
+        }
+        if let Some(patch) = self.pending_edits.get(&jotform.id) {
+            patch.apply_to(jotform);
+        }
+    }
+
+    pub fn handle_key_events(&mut self, key_event: KeyEvent) {
+        self.notice = None;
+        if !self.errors.is_empty() {
+            match key_event.code {
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char(' ') => {
+                    self.errors.pop_front();
+                }
+                KeyCode::Char('q') => self.exit(),
+                _ => {}
+            }
+            return;
+        }
+        if !self.conflicts.is_empty() {
+            match key_event.code {
+                KeyCode::Char('k') => self.resolve_conflict(Resolution::KeepMine),
+                KeyCode::Char('t') => self.resolve_conflict(Resolution::TakeTheirs),
+                KeyCode::Char('r') => self.resolve_conflict(Resolution::Reapply),
+                KeyCode::Char('q') => self.exit(),
+                _ => {}
+            }
+            return;
+        }
+        if !self.startup.is_done() {
+            self.handle_startup_keys(key_event);
+            return;
+        }
+        if self.search.editing {
+            self.handle_search_input(key_event);
+            return;
+        }
+        if self.filter_panel.editing_date {
+            self.handle_date_input(key_event);
+            return;
+        }
+        if self.filter_panel.open {
+            self.handle_filter_panel_keys(key_event);
+            return;
+        }
+        if self.edit_form.is_some() {
+            self.handle_edit_form_keys(key_event);
+            return;
+        }
+        if self.new_ticket.is_some() {
+            self.handle_new_ticket_keys(key_event);
+            return;
+        }
+        if self.status_menu.open {
+            self.handle_status_menu_keys(key_event);
+            return;
+        }
+        if self.assignee_picker.open {
+            self.handle_assignee_picker_keys(key_event);
+            return;
+        }
+        if self.note_input.is_some() {
+            self.handle_note_input(key_event);
+            return;
+        }
+        if self.export_prompt.is_some() {
+            self.handle_export_keys(key_event);
+            return;
+        }
+        if self.dashboard_open {
+            self.handle_dashboard_keys(key_event);
+            return;
+        }
+        if self.detail_open {
+            self.handle_detail_keys(key_event);
+            return;
+        }
+
+        match key_event.code {
+            KeyCode::Char('q') => self.exit(),
+
+            KeyCode::Up => self.select_relative(-1, false),
+            KeyCode::Down => self.select_relative(1, false),
+            KeyCode::Enter if !self.selected_id.is_empty() => self.open_detail(),
+            KeyCode::Tab => self.dashboard_open = true,
+            KeyCode::Char('e') => self.open_status_menu(),
+            KeyCode::Char('c') => self.open_edit_form(),
+            KeyCode::Char('a') => self.open_assignee_picker(),
+            KeyCode::Char('r') => self.start_refresh(),
+
+            KeyCode::Char('s') => {
+                self.sort.column = self.sort.column.next();
+                self.sort.apply(&mut self.jotforms);
+            }
+            KeyCode::Char('S') => {
+                self.sort.direction = self.sort.direction.toggle();
+                self.sort.apply(&mut self.jotforms);
+            }
+
+            KeyCode::Char('/') => self.search.editing = true,
+            KeyCode::Char('f') => self.filter_panel.open = true,
+            KeyCode::Char('m') => self.toggle_mine(),
+            KeyCode::Char('n') if self.search.is_active() => self.select_relative(1, true),
+            KeyCode::Char('N') if self.search.is_active() => self.select_relative(-1, true),
+            KeyCode::Char('n') => self.open_new_ticket(),
+            KeyCode::Char('x') => self.open_export(),
+            KeyCode::Char('p') => self.print_work_orders(false),
+            KeyCode::Char('P') => self.print_work_orders(true),
+            KeyCode::Esc if self.search.is_active() => {
+                self.search.clear();
+                self.ensure_selection_visible();
+            }
+
+            KeyCode::PageUp => {
+                self.description_offset = self.description_offset.saturating_sub(1);
+            }
+            KeyCode::PageDown => {
+                self.description_offset = self.description_offset.saturating_add(1);
+            }
+
+            _ => {}
+        }
+    }
+
+    /// The dashboard only reads, so little besides leaving it does anything.
+    fn handle_dashboard_keys(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('q') => self.exit(),
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Esc => self.dashboard_open = false,
+            KeyCode::Char('r') => self.start_refresh(),
+            _ => {}
+        }
+    }
+
+    fn open_detail(&mut self) {
+        self.detail_open = true;
+        self.detail_pane = DetailPane::default();
+        self.description_offset = 0;
+        self.notes_offset = 0;
+        self.load_notes();
+    }
+
+    /// Keys in the full-screen detail view of the selected jotform.
+    fn handle_detail_keys(&mut self, key_event: KeyEvent) {
+        let offset = match self.detail_pane {
+            DetailPane::Description => &mut self.description_offset,
+            DetailPane::Notes => &mut self.notes_offset,
+        };
+        match key_event.code {
+            KeyCode::Char('q') => self.exit(),
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Backspace => self.detail_open = false,
+            KeyCode::Tab => self.detail_pane = self.detail_pane.toggle(),
+            KeyCode::Up => *offset = offset.saturating_sub(1),
+            KeyCode::Down => *offset = offset.saturating_add(1),
+            KeyCode::PageUp => *offset = offset.saturating_sub(10),
+            KeyCode::PageDown => *offset = offset.saturating_add(10),
+            KeyCode::Home => *offset = 0,
+            KeyCode::Left | KeyCode::Right => {
+                let previous = self.selected_id.clone();
+                let delta = if key_event.code == KeyCode::Left {
+                    -1
+                } else {
+                    1
+                };
+                self.select_relative(delta, false);
+                if self.selected_id != previous {
+                    self.notes_offset = 0;
+                    self.load_notes();
+                }
+            }
+            KeyCode::Char('e') => self.open_status_menu(),
+            KeyCode::Char('c') => self.open_edit_form(),
+            KeyCode::Char('a') => self.open_assignee_picker(),
+            KeyCode::Char('w') => self.open_note_input(),
+            KeyCode::Char('p') => self.print_work_orders(false),
+            _ => {}
+        }
+    }
+
+    fn open_note_input(&mut self) {
+        if !self.refuse_offline_edit() && !self.selected_id.is_empty() {
+            self.note_input = Some(NoteInput::new(&self.selected_id));
+        }
+    }
+
+    /// Keys typed into the new-note input.
+    fn handle_note_input(&mut self, key_event: KeyEvent) {
+        let Some(input) = &mut self.note_input else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.note_input = None,
+            KeyCode::Char('s') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.start_add_note();
+            }
+            KeyCode::Enter => input.text.push('\n'),
+            KeyCode::Backspace => {
+                input.text.pop();
+            }
+            KeyCode::Char(c) => input.text.push(c),
+            _ => {}
+        }
+    }
+
+    /// Writes a work order for the selected ticket, or with `all` for every
+    /// open High-priority one.
+    fn print_work_orders(&mut self, all: bool) {
+        let now = Local::now().naive_local();
+        let report = if all {
+            Report::high_priority(&self.jotforms, now)
+        } else {
+            match self.jotforms.iter().find(|j| j.id == self.selected_id) {
+                Some(jotform) => Report::single(jotform, now),
+                None => return,
+            }
+        };
+        if report.jotforms.is_empty() {
+            self.show_error(
+                "No work orders",
+                "There are no open or in-progress High-priority tickets.",
+            );
+            return;
+        }
+        match report::write(&self.config.report, &report) {
+            Ok([html, text]) => {
+                self.notice = Some(format!("Wrote {} and {}", html.display(), text.display()));
+            }
+            Err(e) => self.show_error("Could not write the work order", e.to_string()),
+        }
+    }
+
+    fn open_export(&mut self) {
+        if self.visible_jotforms().is_empty() {
+            self.show_error(
+                "Nothing to export",
+                "No tickets match the current filter and search.",
+            );
+        } else {
+            self.export_prompt = Some(ExportPrompt::new(Local::now().naive_local()));
+        }
+    }
+
+    /// Keys in the export prompt: Tab picks the format, the rest edit the
+    /// file name.
+    fn handle_export_keys(&mut self, key_event: KeyEvent) {
+        let Some(prompt) = &mut self.export_prompt else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.export_prompt = None,
+            KeyCode::Enter => self.save_export(),
+            KeyCode::Tab => prompt.cycle_format(false),
+            KeyCode::BackTab => prompt.cycle_format(true),
+            KeyCode::Backspace => prompt.pop(),
+            KeyCode::Char(c) => prompt.push(c),
+            _ => {}
+        }
+    }
+
+    /// Writes the rows on screen, in their current order, to the prompt's
+    /// file. An existing file is only replaced on a second Enter.
+    fn save_export(&mut self) {
+        let Some(prompt) = &self.export_prompt else {
+            return;
+        };
+        let format = prompt.format;
+        let path = match prompt.target() {
+            Ok(path) if path.exists() && !prompt.overwrite => {
+                if let Some(prompt) = &mut self.export_prompt {
+                    prompt.error =
+                        Some("That file exists; press Enter again to replace it".to_string());
+                    prompt.overwrite = true;
+                }
+                return;
+            }
+            Ok(path) => path,
+            Err(error) => {
+                if let Some(prompt) = &mut self.export_prompt {
+                    prompt.error = Some(error);
+                }
+                return;
+            }
+        };
+        let rows = self.visible_jotforms();
+        let count = rows.len();
+        match export::write(&path, format, &rows) {
+            Ok(()) => {
+                self.export_prompt = None;
+                self.notice = Some(format!(
+                    "Exported {} ticket(s) to {}",
+                    count,
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                if let Some(prompt) = &mut self.export_prompt {
+                    prompt.error = Some(format!("Could not write the file: {}", e));
+                }
+            }
+        }
+    }
+
+    /// Shows why nothing can be edited while offline; true if so.
+    fn refuse_offline_edit(&mut self) -> bool {
+        if self.offline_since.is_some() {
+            self.show_error(
+                "Offline",
+                "This can't be done offline. Press r to reconnect.",
+            );
+        }
+        self.offline_since.is_some()
+    }
+
+    fn open_status_menu(&mut self) {
+        if let Some(selected_jotform) = self.jotforms.iter().find(|j| j.id == self.selected_id) {
+            self.status_menu.open_at(&selected_jotform.status);
+        }
+    }
+
+    /// Keys handled while the status menu is open.
+    fn handle_status_menu_keys(&mut self, key_event: KeyEvent) {
+        if let Some(target) = self.status_menu.confirming.clone() {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Enter => self.set_selected_status(target),
+                KeyCode::Char('n') | KeyCode::Esc => self.status_menu.confirming = None,
+                _ => {}
+            }
+            return;
+        }
+
+        match key_event.code {
+            KeyCode::Char('q') => self.exit(),
+            KeyCode::Char('e') | KeyCode::Esc => self.status_menu.close(),
+            KeyCode::Up => self.status_menu.move_cursor(-1),
+            KeyCode::Down => self.status_menu.move_cursor(1),
+            KeyCode::Enter => self.choose_status(self.status_menu.highlighted().clone()),
+            KeyCode::Char(c) => {
+                if let Some(status) = status_menu::shortcut(c) {
+                    self.choose_status(status.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Applies a status picked from the menu, asking first if that is
+    /// configured for it.
+    fn choose_status(&mut self, target: Status) {
+        let Some(selected_jotform) = self.jotforms.iter().find(|j| j.id == self.selected_id) else {
+            self.status_menu.close();
+            return;
+        };
+        if self.config.confirm_high_priority_close
+            && status_menu::needs_confirmation(selected_jotform, &target)
+        {
+            self.status_menu.confirming = Some(target);
+        } else {
+            self.set_selected_status(target);
+        }
+    }
+
+    /// Moves the selected jotform to `status` with a single save.
+    fn set_selected_status(&mut self, status: Status) {
+        self.status_menu.close();
+        let Some(selected_jotform) = self.jotforms.iter_mut().find(|j| j.id == self.selected_id)
+        else {
+            return;
+        };
+        if selected_jotform.status == status {
+            return;
+        }
+        let base = selected_jotform.clone();
+        selected_jotform.status = status.clone();
+        self.start_status_save(base, status);
+        self.sort.apply(&mut self.jotforms);
+        self.ensure_selection_visible();
+    }
+
+    fn open_edit_form(&mut self) {
+        if let Some(selected_jotform) = self.jotforms.iter().find(|j| j.id == self.selected_id) {
+            self.edit_form = Some(EditForm::new(selected_jotform));
+        }
+    }
+
+    /// Keys handled while the edit form is open.
+    fn handle_edit_form_keys(&mut self, key_event: KeyEvent) {
+        let Some(form) = &mut self.edit_form else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.edit_form = None,
+            KeyCode::Tab | KeyCode::Down => form.move_focus(1),
+            KeyCode::BackTab | KeyCode::Up => form.move_focus(-1),
+            KeyCode::Left => form.cycle_choice(-1),
+            KeyCode::Right | KeyCode::Char(' ') if form.focused().is_choice() => {
+                form.cycle_choice(1);
+            }
+            KeyCode::Backspace => {
+                if let Some(text) = form.focused_text() {
+                    text.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(text) = form.focused_text() {
+                    text.push(c);
+                }
+            }
+            KeyCode::Enter => match form.patch() {
+                Err(e) => form.error = Some(e),
+                Ok(patch) => {
+                    let id = form.original.id.clone();
+                    self.edit_form = None;
+                    if !patch.is_empty() {
+                        self.start_fields_save(id, patch);
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn open_assignee_picker(&mut self) {
+        if self.selected_id.is_empty() {
+            return;
+        }
+        self.assignee_picker.open_loading();
+        if !self.config.staff.is_empty() {
+            let staff = self.config.staff.clone();
+            self.fill_assignee_picker(&staff);
+        } else if let Some(staff) = self.staff.clone() {
+            self.fill_assignee_picker(&staff);
+        } else {
+            self.commands.push(Command::LoadStaff);
+        }
+    }
+
+    fn fill_assignee_picker(&mut self, staff: &[String]) {
+        let current = self
+            .jotforms
+            .iter()
+            .find(|j| j.id == self.selected_id)
+            .and_then(|j| j.assignee.as_deref());
+        self.assignee_picker
+            .set_staff(staff, self.config.user.as_deref(), current);
+    }
+
+    /// Keys handled while the assignee picker is open.
+    fn handle_assignee_picker_keys(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('q') => self.exit(),
+            KeyCode::Char('a') | KeyCode::Esc => self.assignee_picker.close(),
+            KeyCode::Up => self.assignee_picker.move_cursor(-1),
+            KeyCode::Down => self.assignee_picker.move_cursor(1),
+            KeyCode::Enter => {
+                if let Some(choice) = self.assignee_picker.highlighted().cloned() {
+                    self.assign_selected(choice);
+                }
+            }
+            KeyCode::Char('m') if self.config.user.is_some() => {
+                self.assign_selected(self.config.user.clone());
+            }
+            KeyCode::Char('u') => self.assign_selected(None),
+            _ => {}
+        }
+    }
+
+    /// Saves a new assignee for the selected jotform, or `None` to unassign.
+    fn assign_selected(&mut self, assignee: Option<String>) {
+        self.assignee_picker.close();
+        let Some(selected_jotform) = self.jotforms.iter().find(|j| j.id == self.selected_id) else {
+            return;
+        };
+        if selected_jotform.assignee == assignee {
+            return;
+        }
+        let patch = JotformPatch {
+            assignee: Some(assignee),
+            ..JotformPatch::default()
+        };
+        self.start_fields_save(selected_jotform.id.clone(), patch);
+    }
+
+    /// The "mine" quick filter: only tickets assigned to the configured user.
+    fn toggle_mine(&mut self) {
+        let Some(user) = &self.config.user else {
+            self.show_error(
+                "No current user",
+                "Set `user` in the config file, JOTVIEW_USER or --user to filter by your tickets.",
+            );
+            return;
+        };
+        self.filter.assignee = match self.filter.assignee {
+            Some(_) => None,
+            None => Some(user.clone()),
+        };
+        self.ensure_selection_visible();
+    }
+
+    fn open_new_ticket(&mut self) {
+        if !self.refuse_offline_edit() {
+            self.new_ticket = Some(NewTicketForm::default());
+        }
+    }
+
+    /// Keys handled while the new-ticket form is open.
+    fn handle_new_ticket_keys(&mut self, key_event: KeyEvent) {
+        let Some(form) = &mut self.new_ticket else {
+            return;
+        };
+        let in_description = form.focused() == TicketField::Description;
+        match key_event.code {
+            KeyCode::Esc => self.new_ticket = None,
+            KeyCode::Char('s') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.start_create();
+            }
+            KeyCode::Enter if in_description => form.description.push('\n'),
+            KeyCode::Enter => self.start_create(),
+            KeyCode::Tab | KeyCode::Down => form.move_focus(1),
+            KeyCode::BackTab | KeyCode::Up => form.move_focus(-1),
+            KeyCode::Left => form.cycle_choice(-1),
+            KeyCode::Right | KeyCode::Char(' ') if form.focused().is_choice() => {
+                form.cycle_choice(1);
+            }
+            KeyCode::Backspace => {
+                if let Some(text) = form.focused_text() {
+                    text.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(text) = form.focused_text() {
+                    text.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Keys on the connecting / connection error screen.
+    fn handle_startup_keys(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.exit(),
+            KeyCode::Char('r') => {
+                if let Startup::Failed(failure) = &mut self.startup {
+                    // Retrying by hand opts into automatic retries with backoff.
+                    failure.retry_at = Some(Instant::now());
+                    self.start_initial_load();
+                }
+            }
+            KeyCode::Char('c') if matches!(self.startup, Startup::Failed(_)) => {
+                self.edit_config_requested = true;
+            }
+            KeyCode::Char('o') => self.start_offline(),
+            _ => {}
+        }
+    }
+
+    /// Keys typed while the `/` input line is open.
+    fn handle_search_input(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Enter => self.search.editing = false,
+            KeyCode::Esc => self.search.clear(),
+            KeyCode::Backspace => {
+                self.search.query.pop();
+            }
+            KeyCode::Char(c) => self.search.query.push(c),
+            _ => return,
+        }
+        self.ensure_selection_visible();
+    }
+
+    /// Keys handled while the filter panel has focus.
+    fn handle_filter_panel_keys(&mut self, key_event: KeyEvent) {
+        let items = filter::items(&self.jotforms, &self.filter);
+        self.filter_panel.cursor = self.filter_panel.cursor.min(items.len() - 1);
+
+        match key_event.code {
+            KeyCode::Char('q') => self.exit(),
+            KeyCode::Char('f') | KeyCode::Esc => self.filter_panel.open = false,
+            KeyCode::Up => {
+                self.filter_panel.cursor = self.filter_panel.cursor.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                self.filter_panel.cursor = (self.filter_panel.cursor + 1).min(items.len() - 1);
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => match &items[self.filter_panel.cursor] {
+                FilterItem::DateRange => {
+                    self.filter_panel.editing_date = true;
+                    self.filter_panel.date_error = None;
+                    self.filter_panel.date_input = self
+                        .filter
+                        .date_range
+                        .as_ref()
+                        .map(|range| range.input.clone())
+                        .unwrap_or_default();
+                }
+                item => {
+                    self.filter.toggle(item);
+                    self.ensure_selection_visible();
+                }
+            },
+            KeyCode::Char('c') => {
+                self.filter.clear();
+                self.ensure_selection_visible();
+            }
+            _ => {}
+        }
+    }
+
+    /// Keys typed while the filter panel's date-range input has focus.
+    fn handle_date_input(&mut self, key_event: KeyEvent) {
+        let panel = &mut self.filter_panel;
+        match key_event.code {
+            KeyCode::Esc => {
+                panel.editing_date = false;
+                panel.date_error = None;
+            }
+            KeyCode::Backspace => {
+                panel.date_input.pop();
+            }
+            KeyCode::Char(c) => panel.date_input.push(c),
+            KeyCode::Enter if panel.date_input.trim().is_empty() => {
+                panel.editing_date = false;
+                panel.date_error = None;
+                self.filter.date_range = None;
+                self.ensure_selection_visible();
+            }
+            KeyCode::Enter => {
+                let today = chrono::Local::now().date_naive();
+                match DateRange::parse(&panel.date_input, today) {
+                    Ok(range) => {
+                        panel.editing_date = false;
+                        panel.date_error = None;
+                        self.filter.date_range = Some(range);
+                        self.ensure_selection_visible();
+                    }
+                    Err(e) => panel.date_error = Some(e),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Jotforms that pass the current search and filter, in display order.
+    pub fn visible_jotforms(&self) -> Vec<&Jotform> {
+        self.jotforms
+            .iter()
+            .filter(|j| self.filter.matches(j) && self.search.matches(j))
+            .collect()
+    }
+
+    /// Moves the selection `delta` rows through the visible list, optionally
+    /// wrapping around either end.
+    fn select_relative(&mut self, delta: isize, wrap: bool) {
+        let visible = self.visible_jotforms();
+        if visible.is_empty() {
+            return;
+        }
+        let len = visible.len() as isize;
+        let target = match visible.iter().position(|j| j.id == self.selected_id) {
+            Some(current) if wrap => (current as isize + delta).rem_euclid(len),
+            Some(current) => (current as isize + delta).clamp(0, len - 1),
+            None => 0,
+        };
+        let id = visible[target as usize].id.clone();
+        if id != self.selected_id {
+            self.selected_id = id;
+            self.description_offset = 0;
+        }
+    }
+
+    /// Keeps `selected_id` pointing at a visible row after the visible set
+    /// changes, falling back to the first one (or nothing).
+    fn ensure_selection_visible(&mut self) {
+        let visible = self.visible_jotforms();
+        if visible.iter().any(|j| j.id == self.selected_id) {
+            return;
+        }
+        let id = visible.first().map(|j| j.id.clone()).unwrap_or_default();
+        self.selected_id = id;
+        self.description_offset = 0;
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+    }
+}
+
+/// Periodic refresh timer. The first tick of an interval fires immediately,
+/// so it starts one period out.
+fn refresh_timer(period: Option<Duration>) -> Option<Interval> {
+    period.map(|period| {
+        let mut interval = time::interval_at(Instant::now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        interval
+    })
+}
+
+/// Waits until `deadline`, or forever if there is none.
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// `$VISUAL` or `$EDITOR` (which may include arguments), falling back to `vi`.
+fn editor_command(path: &Path) -> process::Command {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let mut command = process::Command::new(parts.next().unwrap_or("vi"));
+    command.args(parts).arg(path);
+    command
+}
+
+/// Waits for the next tick, or forever if there is no interval.
+async fn next_tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        headless::Headless,
+        model::Priority,
+        sort::SortColumn,
+        test_support::{self, name, submitted},
+    };
+
+    /// Submitted on day `id` of May 2024, at revision 1.
+    fn jotform(id: &str, first: &str, status: Status) -> Jotform {
+        Jotform {
+            submitter_name: name(first),
+            created_at: submitted(&format!("2024-05-0{}", id)),
+            status,
+            updated_at: Some("1".to_string()),
+            ..test_support::jotform(id)
+        }
+    }
+
+    fn headless() -> Headless {
+        Headless::new(vec![
+            jotform("1", "Cy", Status::Open),
+            jotform("2", "Ann", Status::InProgress),
+            jotform("3", "Bo", Status::Closed),
+        ])
+    }
+
+    #[test]
+    fn keys_move_the_selection_scroll_and_re_sort() {
+        let mut driver = headless();
+        assert_eq!(driver.order(), ["2", "3", "1"]);
+        assert_eq!(driver.app.selected_id, "2");
+
+        driver.keys("<Down><Down><Down><Up>");
+        assert_eq!(driver.app.selected_id, "3");
+        driver.keys("<PgDn><PgDn>");
+        assert_eq!(driver.app.description_offset, 2);
+
+        // Assignee, then Submitter; the selection follows its row.
+        driver.keys("ss");
+        assert_eq!(driver.app.sort.column, SortColumn::Submitter);
+        assert_eq!(driver.order(), ["2", "3", "1"]);
+        driver.keys("S");
+        assert_eq!(driver.order(), ["1", "3", "2"]);
+        assert_eq!(driver.app.selected_id, "3");
+
+        driver.keys("<Down>");
+        assert_eq!(driver.app.selected_id, "2");
+        assert_eq!(driver.app.description_offset, 0);
+        assert!(driver.commands.is_empty());
+    }
+
+    #[test]
+    fn n_jumps_between_matches_and_otherwise_logs_a_ticket() {
+        let mut atrium = vec![
+            jotform("1", "Cy", Status::Open),
+            jotform("2", "Ann", Status::InProgress),
+            jotform("3", "Bo", Status::Closed),
+        ];
+        atrium[0].location = "Atrium".to_string();
+        atrium[2].location = "Atrium".to_string();
+        let mut driver = Headless::new(atrium);
+
+        driver.keys("/atrium<Enter>");
+        assert_eq!(driver.app.selected_id, "3");
+        driver.keys("n");
+        assert_eq!(driver.app.selected_id, "1");
+        driver.keys("n");
+        assert_eq!(driver.app.selected_id, "3");
+        driver.keys("N");
+        assert_eq!(driver.app.selected_id, "1");
+        assert!(driver.app.new_ticket.is_none());
+
+        driver.keys("<Esc>");
+        assert!(!driver.app.search.is_active());
+        driver.keys("n");
+        assert!(driver.app.new_ticket.is_some());
+        assert_eq!(driver.app.selected_id, "1");
+    }
+
+    #[test]
+    fn esc_closes_the_topmost_popup_first() {
+        let mut driver = headless();
+        driver.keys("/ann<Enter><Enter>w");
+        driver.app.show_error("Could not save", "offline");
+
+        driver.keys("<Esc>");
+        assert!(driver.app.errors.is_empty());
+        assert!(driver.app.note_input.is_some());
+        driver.keys("<Esc>");
+        assert!(driver.app.note_input.is_none());
+        assert!(driver.app.detail_open);
+        driver.keys("e<Esc>");
+        assert!(!driver.app.status_menu.open);
+        assert!(driver.app.detail_open);
+        driver.keys("<Esc>");
+        assert!(!driver.app.detail_open);
+        assert!(driver.app.search.is_active());
+        driver.keys("<Esc>");
+        assert!(!driver.app.search.is_active());
+        driver.keys("<Esc>");
+        assert!(!driver.app.exit);
+    }
+
+    #[test]
+    fn o_opens_the_cached_copy_once_retries_are_waiting_on_the_backend() {
+        let dir = env::temp_dir().join(format!("jotview-offline-{}", process::id()));
+        let cache = Cache::new(dir.join("cache.json"));
+        cache.save(&[jotform("1", "Cy", Status::Open)]).unwrap();
+        let mut driver = headless();
+        driver.app.cache = Some(cache.clone());
+        let fail = |driver: &mut Headless, auto_retry| {
+            driver.app.startup = Startup::Connecting {
+                attempt: 1,
+                auto_retry,
+            };
+            driver
+                .app
+                .handle_message(Message::Loaded(Err(JotviewError::NotFound {
+                    id: "list".to_string(),
+                })));
+        };
+
+        // Retrying by hand waits for the backend; `o` is still there.
+        fail(&mut driver, true);
+        let Startup::Failed(failure) = &driver.app.startup else {
+            panic!("expected the connection error screen");
+        };
+        assert_eq!(failure.offline_copy.map(|(_, count)| count), Some(1));
+        driver.keys("o");
+        assert!(driver.app.startup.is_done());
+        assert!(driver.app.offline_since.is_some());
+        assert_eq!(driver.order(), ["1"]);
+
+        // Without a readable copy, `o` says so.
+        fs::write(cache.path(), "{ not json").unwrap();
+        fail(&mut driver, false);
+        let Startup::Failed(failure) = &driver.app.startup else {
+            panic!("expected the connection error screen");
+        };
+        assert!(failure.offline_copy.is_none());
+        driver.keys("o");
+        assert!(!driver.app.startup.is_done());
+        assert_eq!(
+            driver.app.errors.front().unwrap().title,
+            "Could not read the offline copy"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn status_change_shows_at_once_and_asks_for_a_save() {
+        let mut driver = headless();
+        driver.keys("e3");
+
+        assert_eq!(
+            driver
+                .app
+                .jotforms
+                .iter()
+                .find(|j| j.id == "2")
+                .unwrap()
+                .status,
+            Status::Closed
+        );
+        assert_eq!(driver.order(), ["3", "2", "1"]);
+        assert!(matches!(
+            driver.commands.as_slice(),
+            [Command::SaveStatus { base, status: Status::Closed }]
+                if base.id == "2" && base.status == Status::InProgress
+        ));
+    }
+
+    #[tokio::test]
+    async fn commands_run_against_the_backend() {
+        let mut driver = headless();
+        driver.keys("e3<Enter>").settle().await;
+
+        let stored = driver.backend.get("2").await.unwrap();
+        assert_eq!(stored.status, Status::Closed);
+        let shown = driver.app.jotforms.iter().find(|j| j.id == "2").unwrap();
+        assert_eq!(shown.updated_at, stored.updated_at);
+        assert!(driver.app.pending_saves.is_empty());
+
+        assert!(matches!(
+            driver.commands.last(),
+            Some(Command::LoadNotes { id }) if id == "2"
+        ));
+        assert!(driver.app.notes["2"].notes.is_empty());
+        assert!(driver.app.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn refreshes_keep_field_edits_that_are_not_saved_yet() {
+        let mut driver = headless();
+        let server = driver.app.jotforms.clone();
+        let priority = |driver: &Headless, id: &str| {
+            let jotform = driver.app.jotforms.iter().find(|j| j.id == id).unwrap();
+            jotform.priority_level.clone()
+        };
+
+        // Offline, the edit waits in the outbox.
+        driver.app.offline_since = Some(Local::now());
+        driver.keys("c<Right><Enter>");
+        assert!(driver.commands.is_empty());
+        driver
+            .app
+            .handle_message(Message::Refreshed(Ok(server.clone())));
+        assert_eq!(priority(&driver, "2"), Priority::Medium);
+
+        // Back online, a second edit is in flight when the next refresh lands.
+        driver.keys("<Down>c<Right><Enter>");
+        assert!(driver.app.pending_edits.contains_key("3"));
+        driver.app.handle_message(Message::Refreshed(Ok(server)));
+        assert_eq!(priority(&driver, "2"), Priority::Medium);
+        assert_eq!(priority(&driver, "3"), Priority::Medium);
+
+        driver.settle().await;
+        assert_eq!(priority(&driver, "3"), Priority::Medium);
+        let stored = driver.backend.get("3").await.unwrap();
+        assert_eq!(stored.priority_level, Priority::Medium);
+        assert!(driver.app.errors.is_empty());
+    }
+
+    #[test]
+    fn export_writes_the_rows_on_screen_and_asks_before_replacing() {
+        let dir = env::temp_dir().join(format!("jotview-export-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("open.md");
+        let _ = fs::remove_file(&path);
+
+        let mut driver = headless();
+        driver.keys("/ann<Enter>x<Tab><Tab>");
+        let prompt = driver.app.export_prompt.as_mut().unwrap();
+        assert!(prompt.path.ends_with(".md"), "{}", prompt.path);
+        prompt.path = path.display().to_string();
+        driver.keys("<Enter>");
+
+        assert!(driver.app.export_prompt.is_none());
+        let written = fs::read_to_string(&path).unwrap();
+        let rows: Vec<&str> = written.lines().skip(2).collect();
+        assert_eq!(rows.len(), 1, "{written}");
+        assert!(rows[0].starts_with("| Ann | 05-02-2024 |"), "{written}");
+        assert!(driver.app.notice.as_ref().unwrap().contains("1 ticket(s)"));
+
+        driver.keys("x");
+        driver.app.export_prompt.as_mut().unwrap().path = path.display().to_string();
+        driver.keys("<Enter>");
+        assert!(driver.app.export_prompt.as_ref().unwrap().error.is_some());
+        driver.keys("<Enter>");
+        assert!(driver.app.export_prompt.is_none());
+        assert!(fs::read_to_string(&path).unwrap().starts_with("Submitter,"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
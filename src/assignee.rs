@@ -0,0 +1,84 @@
+/// Popup for assigning the selected jotform, opened with `a`.
+#[derive(Debug, Default)]
+pub struct AssigneePicker {
+    pub open: bool,
+    /// Rows of the picker, "Unassigned" (`None`) first; `None` while the
+    /// staff list is being fetched.
+    pub choices: Option<Vec<Option<String>>>,
+    pub cursor: usize,
+}
+
+impl AssigneePicker {
+    /// Opens the picker before the staff list is known.
+    pub fn open_loading(&mut self) {
+        *self = AssigneePicker {
+            open: true,
+            ..AssigneePicker::default()
+        };
+    }
+
+    /// Fills in the rows with the cursor on the current assignee.
+    pub fn set_staff(&mut self, staff: &[String], user: Option<&str>, current: Option<&str>) {
+        let choices = choices(staff, user, current);
+        self.cursor = choices
+            .iter()
+            .position(|choice| choice.as_deref() == current)
+            .unwrap_or(0);
+        self.choices = Some(choices);
+    }
+
+    pub fn close(&mut self) {
+        *self = AssigneePicker::default();
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        let Some(choices) = &self.choices else {
+            return;
+        };
+        let last = choices.len() as isize - 1;
+        self.cursor = (self.cursor as isize + delta).clamp(0, last) as usize;
+    }
+
+    pub fn highlighted(&self) -> Option<&Option<String>> {
+        self.choices.as_ref()?.get(self.cursor)
+    }
+}
+
+/// "Unassigned", then everyone who can be picked in name order: the staff
+/// list plus the current user and current assignee if it lacks them.
+fn choices(staff: &[String], user: Option<&str>, current: Option<&str>) -> Vec<Option<String>> {
+    let mut names: Vec<String> = staff
+        .iter()
+        .map(|name| name.trim().to_string())
+        .chain(user.map(str::to_string))
+        .chain(current.map(str::to_string))
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names.dedup();
+    std::iter::once(None)
+        .chain(names.into_iter().map(Some))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choices_include_user_and_current_once() {
+        let staff = ["Bo Smith".to_string(), "ann lee".to_string()];
+        let mut picker = AssigneePicker::default();
+        picker.set_staff(&staff, Some("Bo Smith"), Some("Cy Park"));
+        assert_eq!(
+            picker.choices.as_deref().unwrap(),
+            [
+                None,
+                Some("ann lee".to_string()),
+                Some("Bo Smith".to_string()),
+                Some("Cy Park".to_string()),
+            ]
+        );
+        assert_eq!(picker.cursor, 3);
+    }
+}
@@ -0,0 +1,462 @@
+use async_trait::async_trait;
+use chrono::Local;
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    api::RestClient,
+    config::{Backend, Config},
+    error::{JotviewError, Result},
+    jotform::JotformClient,
+    model::{Jotform, JotformPatch, NewJotform, Note, Status},
+};
+
+/// Where tickets are kept, picked by `backend` in the config.
+///
+/// Implementations are shared between background tasks, so every method
+/// takes `&self`.
+#[async_trait]
+pub trait TicketBackend: fmt::Debug + Send + Sync {
+    /// Where the tickets come from, shown when they can't be loaded.
+    fn location(&self) -> String;
+
+    async fn list(&self) -> Result<Vec<Jotform>>;
+
+    async fn get(&self, id: &str) -> Result<Jotform>;
+
+    /// Creates a ticket and returns it as stored.
+    async fn create(&self, jotform: &NewJotform) -> Result<Jotform>;
+
+    /// Sets a ticket's status. With `version`, only while the ticket is still
+    /// at that revision; otherwise fails with [`JotviewError::Conflict`].
+    /// Returns the ticket as stored if the backend reports it.
+    async fn update_status(
+        &self,
+        id: &str,
+        status: &Status,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>>;
+
+    /// Saves the fields set in `patch`, guarded by `version` like
+    /// [`TicketBackend::update_status`].
+    async fn update_fields(
+        &self,
+        id: &str,
+        patch: &JotformPatch,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>>;
+
+    /// The ticket's work log, oldest first.
+    async fn notes(&self, _id: &str) -> Result<Vec<Note>> {
+        Err(JotviewError::Unsupported { what: "notes" })
+    }
+
+    /// Appends to the work log and returns the note as stored.
+    async fn add_note(&self, _id: &str, _note: &Note) -> Result<Note> {
+        Err(JotviewError::Unsupported { what: "notes" })
+    }
+
+    /// Names that tickets can be assigned to.
+    async fn staff(&self) -> Result<Vec<String>> {
+        Err(JotviewError::Unsupported {
+            what: "fetching the staff list",
+        })
+    }
+}
+
+/// The backend the config selects. Only a memory backend seeded from a file
+/// touches the disk here.
+pub fn from_config(config: &Config) -> Result<Arc<dyn TicketBackend>> {
+    Ok(match &config.backend {
+        Backend::Rest => Arc::new(RestClient::new(config.base_url.clone())),
+        Backend::Jotform(jotform) => Arc::new(JotformClient::new((**jotform).clone())),
+        Backend::File(path) => Arc::new(FileBackend::new(path.clone())),
+        Backend::Memory(seed) => {
+            let jotforms = match seed {
+                Some(path) => FileBackend::new(path.clone()).read()?,
+                None => Vec::new(),
+            };
+            Arc::new(MemoryBackend::new(jotforms))
+        }
+    })
+}
+
+/// Tickets held by jotview itself, for the file and memory backends.
+#[derive(Debug, Default)]
+struct Tickets {
+    jotforms: Vec<Jotform>,
+    notes: HashMap<String, Vec<Note>>,
+}
+
+impl Tickets {
+    fn get(&self, id: &str) -> Result<&Jotform> {
+        self.jotforms
+            .iter()
+            .find(|j| j.id == id)
+            .ok_or_else(|| JotviewError::NotFound { id: id.to_string() })
+    }
+
+    /// Changes a ticket if it is still at `version`, and stamps it with a
+    /// new revision.
+    fn update(
+        &mut self,
+        id: &str,
+        version: Option<&str>,
+        location: &str,
+        change: impl FnOnce(&mut Jotform),
+    ) -> Result<Jotform> {
+        let jotform = self
+            .jotforms
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or_else(|| JotviewError::NotFound { id: id.to_string() })?;
+        if version.is_some_and(|version| jotform.updated_at.as_deref() != Some(version)) {
+            return Err(JotviewError::Conflict {
+                url: location.to_string(),
+                current: Some(Box::new(jotform.clone())),
+            });
+        }
+        change(jotform);
+        jotform.updated_at = Some(revision());
+        Ok(jotform.clone())
+    }
+
+    /// Adds a ticket under the next free numeric id.
+    fn create(&mut self, new: &NewJotform) -> Jotform {
+        let next_id = self
+            .jotforms
+            .iter()
+            .filter_map(|j| j.id.parse::<u64>().ok())
+            .max()
+            .map_or(1, |id| id + 1);
+        let jotform = Jotform {
+            id: next_id.to_string(),
+            submitter_name: new.submitter_name.clone(),
+            created_at: new.created_at.clone(),
+            location: new.location.clone(),
+            exhibit_name: new.exhibit_name.clone(),
+            description: new.description.clone(),
+            priority_level: new.priority_level.clone(),
+            department: new.department.clone(),
+            status: new.status.clone(),
+            assignee: None,
+            updated_at: Some(revision()),
+        };
+        self.jotforms.push(jotform.clone());
+        jotform
+    }
+}
+
+/// A fresh revision stamp; the time is handy when reading the file by hand.
+fn revision() -> String {
+    Local::now().format("%Y-%m-%dT%H:%M:%S%.6f").to_string()
+}
+
+/// Tickets that live only as long as the process; for tests and demos.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    tickets: Mutex<Tickets>,
+}
+
+impl MemoryBackend {
+    pub fn new(jotforms: Vec<Jotform>) -> Self {
+        Self {
+            tickets: Mutex::new(Tickets {
+                jotforms,
+                notes: HashMap::new(),
+            }),
+        }
+    }
+
+    fn tickets(&self) -> std::sync::MutexGuard<'_, Tickets> {
+        // A panic elsewhere can't leave the list half-changed: every change
+        // is a single assignment.
+        self.tickets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl TicketBackend for MemoryBackend {
+    fn location(&self) -> String {
+        "memory".to_string()
+    }
+
+    async fn list(&self) -> Result<Vec<Jotform>> {
+        Ok(self.tickets().jotforms.clone())
+    }
+
+    async fn get(&self, id: &str) -> Result<Jotform> {
+        self.tickets().get(id).cloned()
+    }
+
+    async fn create(&self, jotform: &NewJotform) -> Result<Jotform> {
+        Ok(self.tickets().create(jotform))
+    }
+
+    async fn update_status(
+        &self,
+        id: &str,
+        status: &Status,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>> {
+        self.tickets()
+            .update(id, version, "memory", |j| j.status = status.clone())
+            .map(Some)
+    }
+
+    async fn update_fields(
+        &self,
+        id: &str,
+        patch: &JotformPatch,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>> {
+        self.tickets()
+            .update(id, version, "memory", |j| patch.apply_to(j))
+            .map(Some)
+    }
+
+    async fn notes(&self, id: &str) -> Result<Vec<Note>> {
+        let tickets = self.tickets();
+        tickets.get(id)?;
+        Ok(tickets.notes.get(id).cloned().unwrap_or_default())
+    }
+
+    async fn add_note(&self, id: &str, note: &Note) -> Result<Note> {
+        let mut tickets = self.tickets();
+        tickets.get(id)?;
+        tickets
+            .notes
+            .entry(id.to_string())
+            .or_default()
+            .push(note.clone());
+        Ok(note.clone())
+    }
+}
+
+/// Tickets in a local JSON or YAML file, shaped like the server's
+/// `/jotforms` list (YAML for a `.yaml` or `.yml` extension).
+///
+/// The file is re-read for every request, so edits made by hand show up on
+/// the next refresh.
+#[derive(Debug)]
+pub struct FileBackend {
+    path: PathBuf,
+    /// Keeps one read-change-write cycle from interleaving with another.
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl FileBackend {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    fn is_yaml(&self) -> bool {
+        self.path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml")
+    }
+
+    /// Reads the file on the calling thread, for seeding a backend before
+    /// anything async is running.
+    pub fn read(&self) -> Result<Vec<Jotform>> {
+        let contents = fs::read_to_string(&self.path).map_err(|source| self.io_error(source))?;
+        self.parse(&contents)
+    }
+
+    async fn load(&self) -> Result<Vec<Jotform>> {
+        let contents = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|source| self.io_error(source))?;
+        self.parse(&contents)
+    }
+
+    fn parse(&self, contents: &str) -> Result<Vec<Jotform>> {
+        let parsed = if self.is_yaml() {
+            serde_yaml::from_str(contents).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_str(contents).map_err(|e| e.to_string())
+        };
+        parsed.map_err(|reason| JotviewError::Format {
+            path: self.location(),
+            reason,
+        })
+    }
+
+    /// Replaces the file, through a temporary file so a crash never leaves
+    /// it half-written.
+    async fn write(&self, jotforms: &[Jotform]) -> Result<()> {
+        let contents = if self.is_yaml() {
+            serde_yaml::to_string(jotforms).map_err(|e| e.to_string())
+        } else {
+            serde_json::to_string_pretty(jotforms).map_err(|e| e.to_string())
+        }
+        .map_err(|reason| JotviewError::Format {
+            path: self.location(),
+            reason,
+        })?;
+        let temp = temp_path(&self.path);
+        let written = match tokio::fs::write(&temp, contents).await {
+            Ok(()) => tokio::fs::rename(&temp, &self.path).await,
+            Err(e) => Err(e),
+        };
+        written.map_err(|source| self.io_error(source))
+    }
+
+    /// Reads the file, applies `change` and writes the result back.
+    async fn modify<T>(&self, change: impl FnOnce(&mut Tickets) -> Result<T>) -> Result<T> {
+        let _guard = self.lock.lock().await;
+        let mut tickets = Tickets {
+            jotforms: self.load().await?,
+            notes: HashMap::new(),
+        };
+        let changed = change(&mut tickets)?;
+        self.write(&tickets.jotforms).await?;
+        Ok(changed)
+    }
+
+    fn io_error(&self, source: io::Error) -> JotviewError {
+        JotviewError::Io {
+            path: self.location(),
+            source,
+        }
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+#[async_trait]
+impl TicketBackend for FileBackend {
+    fn location(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    async fn list(&self) -> Result<Vec<Jotform>> {
+        self.load().await
+    }
+
+    async fn get(&self, id: &str) -> Result<Jotform> {
+        self.load()
+            .await?
+            .into_iter()
+            .find(|j| j.id == id)
+            .ok_or_else(|| JotviewError::NotFound { id: id.to_string() })
+    }
+
+    async fn create(&self, jotform: &NewJotform) -> Result<Jotform> {
+        self.modify(|tickets| Ok(tickets.create(jotform))).await
+    }
+
+    async fn update_status(
+        &self,
+        id: &str,
+        status: &Status,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>> {
+        let location = self.location();
+        self.modify(|tickets| tickets.update(id, version, &location, |j| j.status = status.clone()))
+            .await
+            .map(Some)
+    }
+
+    async fn update_fields(
+        &self,
+        id: &str,
+        patch: &JotformPatch,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>> {
+        let location = self.location();
+        self.modify(|tickets| tickets.update(id, version, &location, |j| patch.apply_to(j)))
+            .await
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Department, FullName, Priority, SubmissionDate};
+
+    fn new_ticket() -> NewJotform {
+        NewJotform {
+            submitter_name: FullName {
+                first: "Ann".to_string(),
+                last: "Lee".to_string(),
+            },
+            created_at: SubmissionDate {
+                date: "2024-05-01".to_string(),
+                time: "09:30".to_string(),
+            },
+            location: "Lobby".to_string(),
+            exhibit_name: "Dinosaurs".to_string(),
+            description: "Lamp out".to_string(),
+            priority_level: Priority::Low,
+            department: Department::Exhibits,
+            status: Status::Open,
+        }
+    }
+
+    #[tokio::test]
+    async fn memory_backend_checks_the_revision_on_update() {
+        let backend = MemoryBackend::default();
+        let created = backend.create(&new_ticket()).await.unwrap();
+        assert_eq!(created.id, "1");
+
+        let stale = created.updated_at.clone();
+        let stored = backend
+            .update_status("1", &Status::Closed, stale.as_deref())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, Status::Closed);
+
+        let err = backend
+            .update_status("1", &Status::Open, stale.as_deref())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, JotviewError::Conflict { current: Some(ref current), .. } if current.status == Status::Closed)
+        );
+        assert!(matches!(
+            backend.get("9").await,
+            Err(JotviewError::NotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn file_backend_writes_changes_back_in_the_same_format() {
+        let dir = std::env::temp_dir().join(format!("jotview-file-backend-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tickets.yaml");
+        fs::write(&path, "[]\n").unwrap();
+
+        let backend = FileBackend::new(path.clone());
+        backend.create(&new_ticket()).await.unwrap();
+        backend
+            .update_fields(
+                "1",
+                &JotformPatch {
+                    location: Some("Atrium".to_string()),
+                    ..JotformPatch::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let yaml = fs::read_to_string(&path).unwrap();
+        assert!(yaml.contains("location: Atrium"), "{yaml}");
+        assert_eq!(backend.list().await.unwrap()[0].location, "Atrium");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
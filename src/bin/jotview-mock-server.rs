@@ -0,0 +1,387 @@
+//! A stand-in for the jotview REST server, for developing and reproducing
+//! bugs without a real backend. Serves `GET /jotforms` and
+//! `POST /jotforms/{id}/status` from a fixtures file kept in memory.
+
+use chrono::Local;
+use clap::Parser;
+use hyper::{
+    header::{CONTENT_TYPE, IF_MATCH},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, StatusCode,
+};
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+// Shared with the TUI so the JSON can't drift from what it expects.
+use jotview::model::{Department, FullName, Jotform, Priority, Status, SubmissionDate};
+
+/// Used when no `--fixtures` file is given.
+const DEFAULT_FIXTURES: &str = include_str!("../../fixtures/jotforms.json");
+
+/// Serve fake jotforms for jotview to talk to
+#[derive(Debug, Parser)]
+#[command(name = "jotview-mock-server", version, about)]
+struct Cli {
+    /// Port to listen on
+    #[arg(long, default_value_t = 3030)]
+    port: u16,
+
+    /// JSON or YAML list of tickets to start with (defaults to the bundled fixtures/jotforms.json)
+    #[arg(long, value_name = "PATH")]
+    fixtures: Option<PathBuf>,
+
+    /// Delay every response by MS milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 0)]
+    latency: u64,
+
+    /// Fraction of requests, from 0 to 1, answered with a 500 instead
+    #[arg(long, value_name = "RATE", default_value_t = 0.0, value_parser = parse_rate)]
+    error_rate: f64,
+
+    /// Add a random new submission every SECS seconds
+    #[arg(long, value_name = "SECS")]
+    new_every: Option<u64>,
+
+    /// Seed for the injected errors and new submissions, to replay a run
+    #[arg(long, value_name = "N")]
+    seed: Option<u64>,
+}
+
+fn parse_rate(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(rate),
+        _ => Err("expected a number from 0 to 1".to_string()),
+    }
+}
+
+/// The tickets being served, and the dice for everything random.
+#[derive(Debug)]
+struct Store {
+    jotforms: Vec<Jotform>,
+    rng: Rng,
+}
+
+/// A response: status and JSON body.
+#[derive(Debug, PartialEq)]
+struct Reply {
+    status: StatusCode,
+    body: String,
+}
+
+#[derive(Deserialize)]
+struct StatusUpdate {
+    new_status: Status,
+}
+
+impl Reply {
+    fn json(status: StatusCode, value: &impl serde::Serialize) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string(value).expect("tickets serialize"),
+        }
+    }
+
+    fn error(status: StatusCode, message: &str) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message }))
+    }
+}
+
+impl Store {
+    fn handle(
+        &mut self,
+        method: &Method,
+        path: &str,
+        if_match: Option<&str>,
+        body: &[u8],
+    ) -> Reply {
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match (method, segments.as_slice()) {
+            (&Method::GET, ["jotforms"]) => Reply::json(StatusCode::OK, &self.jotforms),
+            (&Method::POST, ["jotforms", id, "status"]) => self.set_status(id, if_match, body),
+            (_, ["jotforms"] | ["jotforms", _, "status"]) => {
+                Reply::error(StatusCode::METHOD_NOT_ALLOWED, "method not allowed")
+            }
+            _ => Reply::error(StatusCode::NOT_FOUND, "not found"),
+        }
+    }
+
+    /// Changes a ticket's status, honouring `If-Match` the way jotview
+    /// expects: a stale revision gets a 409 with the current ticket.
+    fn set_status(&mut self, id: &str, if_match: Option<&str>, body: &[u8]) -> Reply {
+        let update: StatusUpdate = match serde_json::from_slice(body) {
+            Ok(update) => update,
+            Err(e) => return Reply::error(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+        let Some(jotform) = self.jotforms.iter_mut().find(|j| j.id == id) else {
+            return Reply::error(StatusCode::NOT_FOUND, "no such jotform");
+        };
+        let version = if_match.map(|tag| tag.trim().trim_matches('"'));
+        if version.is_some_and(|version| jotform.updated_at.as_deref() != Some(version)) {
+            return Reply::json(StatusCode::CONFLICT, jotform);
+        }
+        jotform.status = update.new_status;
+        jotform.updated_at = Some(next_revision(jotform.updated_at.as_deref()));
+        Reply::json(StatusCode::OK, jotform)
+    }
+
+    /// Adds a made-up Open ticket, logged now, under the next free id.
+    fn add_random(&mut self) -> &Jotform {
+        const FIRST: &[&str] = &["Ann", "Bo", "Cy", "Dee", "Eli", "Fay", "Gus", "Hana"];
+        const LAST: &[&str] = &["Lee", "Smith", "Park", "Ortiz", "Novak", "Reid", "Ito"];
+        const PLACES: &[(&str, &str)] = &[
+            ("Lobby", "Dinosaur Hall"),
+            ("East Wing", "Ocean Life"),
+            ("Gallery 2", "Ancient Egypt"),
+            ("Atrium", "Space Race"),
+            ("Gallery 5", "Gems and Minerals"),
+            ("Cafe", ""),
+        ];
+        const PROBLEMS: &[&str] = &[
+            "Light is out.",
+            "Screen is frozen.",
+            "Visitor reported a loose railing.",
+            "Label is missing.",
+            "Floor is wet near the entrance.",
+        ];
+
+        let next_id = self
+            .jotforms
+            .iter()
+            .filter_map(|j| j.id.parse::<u64>().ok())
+            .max()
+            .map_or(1, |id| id + 1);
+        let now = Local::now();
+        let (location, exhibit) = *self.rng.pick(PLACES);
+        let jotform = Jotform {
+            id: next_id.to_string(),
+            submitter_name: FullName {
+                first: self.rng.pick(FIRST).to_string(),
+                last: self.rng.pick(LAST).to_string(),
+            },
+            created_at: SubmissionDate {
+                date: now.format("%Y-%m-%d").to_string(),
+                time: now.format("%H:%M").to_string(),
+            },
+            location: location.to_string(),
+            exhibit_name: exhibit.to_string(),
+            description: self.rng.pick(PROBLEMS).to_string(),
+            priority_level: self.rng.pick(Priority::KNOWN).clone(),
+            department: self.rng.pick(Department::KNOWN).clone(),
+            status: Status::Open,
+            assignee: None,
+            updated_at: Some(next_revision(None)),
+        };
+        self.jotforms.push(jotform);
+        self.jotforms.last().expect("just pushed")
+    }
+}
+
+/// Revisions are counters, so a stale `If-Match` is easy to spot in the log.
+fn next_revision(current: Option<&str>) -> String {
+    let current: u64 = current.and_then(|r| r.parse().ok()).unwrap_or(0);
+    (current + 1).to_string()
+}
+
+/// Small xorshift generator; plenty for injected faults and made-up tickets.
+#[derive(Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // xorshift never leaves zero.
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// True with probability `p`.
+    fn chance(&mut self, p: f64) -> bool {
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[(self.next() % items.len() as u64) as usize]
+    }
+}
+
+fn read_fixtures(path: Option<&Path>) -> Result<Vec<Jotform>, String> {
+    let Some(path) = path else {
+        return serde_json::from_str(DEFAULT_FIXTURES).map_err(|e| e.to_string());
+    };
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    let yaml = path
+        .extension()
+        .is_some_and(|ext| ext == "yaml" || ext == "yml");
+    let parsed = if yaml {
+        serde_yaml::from_str(&contents).map_err(|e| e.to_string())
+    } else {
+        serde_json::from_str(&contents).map_err(|e| e.to_string())
+    };
+    parsed.map_err(|e| format!("could not parse {}: {}", path.display(), e))
+}
+
+fn lock(store: &Mutex<Store>) -> MutexGuard<'_, Store> {
+    store.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+async fn respond(
+    store: Arc<Mutex<Store>>,
+    latency: Duration,
+    error_rate: f64,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let if_match = request
+        .headers()
+        .get(IF_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = hyper::body::to_bytes(request.into_body())
+        .await
+        .unwrap_or_default();
+
+    tokio::time::sleep(latency).await;
+    let reply = {
+        let mut store = lock(&store);
+        if store.rng.chance(error_rate) {
+            Reply::error(StatusCode::INTERNAL_SERVER_ERROR, "injected error")
+        } else {
+            store.handle(&method, &path, if_match.as_deref(), &body)
+        }
+    };
+    eprintln!("{} {} -> {}", method, path, reply.status.as_u16());
+
+    let response = Response::builder()
+        .status(reply.status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(reply.body))
+        .expect("status and header are valid");
+    Ok(response)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let jotforms = match read_fixtures(cli.fixtures.as_deref()) {
+        Ok(jotforms) => jotforms,
+        Err(e) => {
+            eprintln!("jotview-mock-server: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    let seed = cli.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(1, |since| since.as_nanos() as u64)
+    });
+    let store = Arc::new(Mutex::new(Store {
+        jotforms,
+        rng: Rng::new(seed),
+    }));
+
+    if let Some(secs) = cli.new_every.filter(|&secs| secs > 0) {
+        let store = store.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(secs));
+            // The first tick is immediate; start with the fixtures alone.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let mut store = lock(&store);
+                let added = store.add_random();
+                eprintln!("added jotform {}", added.id);
+            }
+        });
+    }
+
+    let latency = Duration::from_millis(cli.latency);
+    let error_rate = cli.error_rate;
+    let make_service = make_service_fn(move |_| {
+        let store = store.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                respond(store.clone(), latency, error_rate, request)
+            }))
+        }
+    });
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], cli.port));
+    let server = match hyper::Server::try_bind(&addr) {
+        Ok(builder) => builder.serve(make_service),
+        Err(e) => {
+            eprintln!("jotview-mock-server: could not listen on {}: {}", addr, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("serving on http://{} (seed {})", addr, seed);
+    match server.await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("jotview-mock-server: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> Store {
+        Store {
+            jotforms: read_fixtures(None).unwrap(),
+            rng: Rng::new(7),
+        }
+    }
+
+    #[test]
+    fn status_updates_check_the_revision() {
+        let mut store = store();
+        let body = br#"{"new_status":"Closed"}"#;
+
+        let reply = store.handle(&Method::POST, "/jotforms/1/status", Some("\"1\""), body);
+        assert_eq!(reply.status, StatusCode::OK);
+        let stored: Jotform = serde_json::from_str(&reply.body).unwrap();
+        assert_eq!(stored.status, Status::Closed);
+        assert_eq!(stored.updated_at.as_deref(), Some("2"));
+
+        let stale = store.handle(&Method::POST, "/jotforms/1/status", Some("\"1\""), body);
+        assert_eq!(stale.status, StatusCode::CONFLICT);
+        let current: Jotform = serde_json::from_str(&stale.body).unwrap();
+        assert_eq!(current.updated_at.as_deref(), Some("2"));
+
+        let missing = store.handle(&Method::POST, "/jotforms/99/status", None, body);
+        assert_eq!(missing.status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn new_submissions_are_open_and_listed() {
+        let mut store = store();
+        let count = store.jotforms.len();
+        let added = store.add_random().clone();
+        assert_eq!(added.id, (count + 1).to_string());
+        assert_eq!(added.status, Status::Open);
+
+        let reply = store.handle(&Method::GET, "/jotforms", None, b"");
+        let listed: Vec<Jotform> = serde_json::from_str(&reply.body).unwrap();
+        assert_eq!(listed.len(), count + 1);
+        assert_eq!(listed.last().unwrap().id, added.id);
+    }
+}
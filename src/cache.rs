@@ -0,0 +1,157 @@
+use chrono::{DateTime, Local};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{model::Jotform, outbox::Outbox};
+
+/// The last list fetched from a backend, kept on disk for offline use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub saved_at: DateTime<Local>,
+    pub jotforms: Vec<Jotform>,
+}
+
+/// Local JSON cache of the jotforms list, one file per backend.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    path: PathBuf,
+}
+
+impl Cache {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// `$XDG_CACHE_HOME/jotview/<host>_<port>.json` for the given backend, so
+    /// switching between servers never mixes their tickets.
+    pub fn for_base_url(base_url: &Url) -> Option<Self> {
+        let host = base_url.host_str()?;
+        let port = base_url.port_or_known_default().unwrap_or(0);
+        let path: String = format!("{}{}", host, base_url.path())
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let name = format!("{}_{}.json", path.trim_end_matches('_'), port);
+        dirs::cache_dir().map(|dir| Self::new(dir.join("jotview").join(name)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The cached snapshot, or `None` if nothing has been cached yet.
+    pub fn load(&self) -> io::Result<Option<Snapshot>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Replaces the cached snapshot. Writes to a temporary file first so a
+    /// crash never leaves a half-written cache behind.
+    pub fn save(&self, jotforms: &[Jotform]) -> io::Result<()> {
+        let snapshot = Snapshot {
+            saved_at: Local::now(),
+            jotforms: jotforms.to_vec(),
+        };
+        write_json(&self.path, &snapshot)
+    }
+
+    /// Changes queued while offline, stored beside the snapshot.
+    pub fn outbox_path(&self) -> PathBuf {
+        self.path.with_extension("outbox.json")
+    }
+
+    pub fn load_outbox(&self) -> io::Result<Outbox> {
+        let contents = match fs::read_to_string(self.outbox_path()) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Outbox::default()),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Replaces the stored outbox; an empty one removes the file.
+    pub fn save_outbox(&self, outbox: &Outbox) -> io::Result<()> {
+        if outbox.is_empty() {
+            return match fs::remove_file(self.outbox_path()) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            };
+        }
+        write_json(&self.outbox_path(), outbox)
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_vec_pretty(value).map_err(io::Error::other)?;
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, json)?;
+    fs::rename(&temp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::Status, outbox::Change, test_support::jotform};
+
+    #[test]
+    fn snapshot_and_outbox_round_trip_through_the_cache_dir() {
+        let dir = std::env::temp_dir().join(format!("jotview-cache-{}", std::process::id()));
+        let cache = Cache::new(dir.join("nested").join("localhost_3000.json"));
+        assert!(cache.load().unwrap().is_none());
+        assert!(cache.load_outbox().unwrap().is_empty());
+
+        let before = Local::now();
+        let revised = Jotform {
+            updated_at: Some("1".to_string()),
+            ..jotform("1")
+        };
+        cache.save(&[revised, jotform("2")]).unwrap();
+        let snapshot = cache.load().unwrap().expect("a snapshot was saved");
+        assert!(before <= snapshot.saved_at && snapshot.saved_at <= Local::now());
+        let ids: Vec<&str> = snapshot.jotforms.iter().map(|j| j.id.as_str()).collect();
+        assert_eq!(ids, ["1", "2"]);
+        assert_eq!(snapshot.jotforms[0].updated_at.as_deref(), Some("1"));
+
+        let outbox_path = cache.outbox_path();
+        assert_eq!(outbox_path.parent(), cache.path().parent());
+        assert_eq!(
+            outbox_path.file_name().unwrap(),
+            "localhost_3000.outbox.json"
+        );
+        let mut outbox = Outbox::default();
+        outbox.queue(
+            &jotform("1"),
+            Change::Status {
+                status: Status::Closed,
+            },
+        );
+        cache.save_outbox(&outbox).unwrap();
+        assert_eq!(cache.load_outbox().unwrap().len(), 1);
+        cache.save_outbox(&Outbox::default()).unwrap();
+        assert!(!outbox_path.exists());
+
+        fs::write(cache.path(), "{ not json").unwrap();
+        let err = cache.load().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
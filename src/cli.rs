@@ -0,0 +1,129 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use crate::{
+    export::Format,
+    model::{Department, Priority, Status},
+};
+
+/// A Terminal User Interface for interacting with jotforms
+#[derive(Debug, Default, Clone, Parser)]
+#[command(name = "jotview", version, about)]
+pub struct Cli {
+    /// Path to the config file (defaults to $XDG_CONFIG_HOME/jotview/config.toml)
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Base URL of the jotforms backend, e.g. http://localhost:3030
+    #[arg(long, value_name = "URL")]
+    pub base_url: Option<String>,
+
+    /// Re-fetch jotforms every SECS seconds in the background (0 disables)
+    #[arg(long, value_name = "SECS")]
+    pub refresh: Option<u64>,
+
+    /// Your name as it appears in the assignee field, for the "mine" filter
+    #[arg(long, value_name = "NAME")]
+    pub user: Option<String>,
+
+    /// How subcommands print tickets
+    #[arg(long, value_enum, default_value_t, global = true)]
+    pub output: Output,
+
+    /// Run once and print instead of opening the TUI
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum CliCommand {
+    /// Print the tickets, in the TUI's default order
+    List(TicketFilter),
+    /// Print every field of one ticket
+    Show { id: String },
+    /// Change a ticket's status
+    SetStatus {
+        id: String,
+        #[arg(value_parser = parse_status)]
+        status: Status,
+    },
+    /// Write the tickets to a file, as the TUI's `x` does
+    Export {
+        #[command(flatten)]
+        filter: TicketFilter,
+
+        /// File format
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+
+        /// Where to write; defaults to a timestamped file in the current directory, - for stdout
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+    },
+    /// Write printable work orders, as HTML and plain text
+    WorkOrder {
+        /// Ticket to print; without one, every open or in-progress High-priority ticket
+        id: Option<String>,
+
+        /// Directory to write to, instead of [report] dir
+        #[arg(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+}
+
+/// Which tickets `list` and `export` include.
+#[derive(Debug, Default, Clone, Args)]
+pub struct TicketFilter {
+    /// Only these statuses (repeat or separate with commas)
+    #[arg(long, value_name = "STATUS", value_delimiter = ',', value_parser = parse_status)]
+    pub status: Vec<Status>,
+
+    /// Only these priorities
+    #[arg(long, value_name = "PRIORITY", value_delimiter = ',', value_parser = parse_priority)]
+    pub priority: Vec<Priority>,
+
+    /// Only these departments
+    #[arg(long, value_name = "DEPARTMENT", value_delimiter = ',', value_parser = parse_department)]
+    pub department: Vec<Department>,
+
+    /// Only tickets submitted in RANGE, as in the filter panel: 7d, 2024-05-01.., ..2024-05-31
+    #[arg(long, value_name = "RANGE")]
+    pub date: Option<String>,
+
+    /// Only tickets assigned to you (see --user)
+    #[arg(long)]
+    pub mine: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Output {
+    /// Aligned columns for reading
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+fn parse_status(value: &str) -> Result<Status, String> {
+    parse_known(value, Status::KNOWN, Status::as_str)
+}
+
+fn parse_priority(value: &str) -> Result<Priority, String> {
+    parse_known(value, Priority::KNOWN, Priority::as_str)
+}
+
+fn parse_department(value: &str) -> Result<Department, String> {
+    parse_known(value, Department::KNOWN, Department::as_str)
+}
+
+/// One of `known`, by label in any case: `inprogress` is `InProgress`.
+fn parse_known<T: Clone>(value: &str, known: &[T], label: fn(&T) -> &str) -> Result<T, String> {
+    known
+        .iter()
+        .find(|item| label(item).eq_ignore_ascii_case(value.trim()))
+        .cloned()
+        .ok_or_else(|| {
+            let labels: Vec<&str> = known.iter().map(label).collect();
+            format!("expected one of {}", labels.join(", "))
+        })
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use crate::{
+    app::Message,
+    backend::TicketBackend,
+    conflict::{Conflict, Resolution},
+    model::{Jotform, JotformPatch, NewJotform, Note, Status},
+    outbox::QueuedChange,
+};
+
+/// Backend work asked for by a state change. Key and message handlers only
+/// record these; the run loop carries them out in the background and hands
+/// the outcome back as a [`Message`].
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// The first fetch at startup, or a retry of it.
+    Load,
+    Refresh,
+    /// Save `status` to the row that looked like `base`.
+    SaveStatus {
+        base: Jotform,
+        status: Status,
+    },
+    SaveFields {
+        base: Jotform,
+        patch: JotformPatch,
+    },
+    Create(NewJotform),
+    LoadNotes {
+        id: String,
+    },
+    AddNote {
+        id: String,
+        note: Note,
+    },
+    LoadStaff,
+    /// Send queued offline changes in order, starting from the revisions in
+    /// `versions`, keyed by ticket id.
+    Replay {
+        changes: Vec<QueuedChange>,
+        versions: HashMap<String, Option<String>>,
+    },
+    /// Send what it takes to settle `conflict` the chosen way.
+    Resolve {
+        conflict: Box<Conflict>,
+        resolution: Resolution,
+    },
+}
+
+impl Command {
+    /// Carries the command out against `backend`.
+    pub async fn run(self, backend: &dyn TicketBackend) -> Message {
+        match self {
+            Command::Load => Message::Loaded(backend.list().await),
+            Command::Refresh => Message::Refreshed(backend.list().await),
+            Command::SaveStatus { base, status } => {
+                let result = backend
+                    .update_status(&base.id, &status, base.updated_at.as_deref())
+                    .await;
+                Message::StatusSaved {
+                    status,
+                    base,
+                    result,
+                }
+            }
+            Command::SaveFields { base, patch } => {
+                let result = backend
+                    .update_fields(&base.id, &patch, base.updated_at.as_deref())
+                    .await;
+                Message::FieldsSaved {
+                    patch,
+                    base,
+                    result,
+                }
+            }
+            Command::Create(submission) => Message::Created(backend.create(&submission).await),
+            Command::LoadNotes { id } => {
+                let result = backend.notes(&id).await;
+                Message::NotesLoaded { id, result }
+            }
+            Command::AddNote { id, note } => {
+                let result = backend.add_note(&id, &note).await;
+                Message::NoteAdded { id, result }
+            }
+            Command::LoadStaff => Message::StaffLoaded(backend.staff().await),
+            Command::Replay {
+                changes,
+                mut versions,
+            } => {
+                let mut results = Vec::new();
+                for queued in changes {
+                    let version = versions.get(&queued.id).cloned().flatten();
+                    let result = queued
+                        .change
+                        .send(backend, &queued.id, version.as_deref())
+                        .await;
+                    if let Ok(Some(stored)) = &result {
+                        versions.insert(queued.id.clone(), stored.updated_at.clone());
+                    }
+                    results.push((queued, result));
+                }
+                Message::Replayed(results)
+            }
+            Command::Resolve {
+                conflict,
+                resolution,
+            } => {
+                // Each change goes against the revision the last one left.
+                let id = conflict.id().to_string();
+                let mut version = conflict.theirs.updated_at.clone();
+                let mut result = Ok(None);
+                for change in conflict.changes(resolution) {
+                    result = change.send(backend, &id, version.as_deref()).await;
+                    match &result {
+                        Ok(Some(stored)) => version = stored.updated_at.clone(),
+                        Ok(None) => {}
+                        Err(_) => break,
+                    }
+                }
+                Message::Resolved {
+                    conflict,
+                    resolution,
+                    result,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,584 @@
+use reqwest::Url;
+use serde::Deserialize;
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    cli::Cli,
+    jotform::{self, JotformConfig, QuestionIds},
+};
+
+pub const DEFAULT_BASE_URL: &str = "http://localhost:3030";
+
+const CONFIG_PATH_ENV: &str = "JOTVIEW_CONFIG";
+const BASE_URL_ENV: &str = "JOTVIEW_BASE_URL";
+const REFRESH_SECS_ENV: &str = "JOTVIEW_REFRESH_SECS";
+const USER_ENV: &str = "JOTVIEW_USER";
+const JOTFORM_API_KEY_ENV: &str = "JOTVIEW_JOTFORM_API_KEY";
+
+/// Resolved runtime configuration.
+///
+/// Values are layered, later sources winning: built-in defaults, the TOML
+/// config file, `JOTVIEW_*` environment variables, then command-line flags.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Config file the values were read from, if one exists.
+    pub path: Option<PathBuf>,
+    pub base_url: Url,
+    pub backend: Backend,
+    /// How often to re-fetch `/jotforms` in the background; `None` disables it.
+    pub refresh_interval: Option<Duration>,
+    /// Ask before closing a High-priority ticket from the status menu.
+    pub confirm_high_priority_close: bool,
+    /// Who is running jotview, as it appears in the assignee field.
+    pub user: Option<String>,
+    /// Names offered by the assignee picker; empty means ask the backend.
+    pub staff: Vec<String>,
+    pub report: ReportConfig,
+    /// Command-line flags, kept so the config can be reloaded after editing.
+    overrides: Cli,
+}
+
+/// Where tickets are read from and saved to.
+#[derive(Debug, Clone)]
+pub enum Backend {
+    /// The jotview REST server at `base_url`.
+    Rest,
+    /// A form on jotform.com, without a server in between.
+    Jotform(Box<JotformConfig>),
+    /// A local JSON or YAML file of tickets.
+    File(PathBuf),
+    /// Tickets kept in memory, optionally starting from a file's, and never
+    /// saved.
+    Memory(Option<PathBuf>),
+}
+
+/// Where printable work orders get their layout and are written.
+#[derive(Debug, Clone, Default)]
+pub struct ReportConfig {
+    /// Directory whose `work_order.html` and `work_order.txt`, where present,
+    /// replace the built-in templates.
+    pub templates: Option<PathBuf>,
+    /// The current directory if unset.
+    pub dir: Option<PathBuf>,
+}
+
+/// On-disk shape of `config.toml`. Every key is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FileConfig {
+    base_url: Option<String>,
+    backend: Option<String>,
+    jotform: FileJotform,
+    file: FileTickets,
+    report: FileReport,
+    refresh_secs: Option<u64>,
+    confirm_high_priority_close: Option<bool>,
+    user: Option<String>,
+    staff: Vec<String>,
+}
+
+/// The `[jotform]` table, only read with `backend = "jotform"`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FileJotform {
+    api_url: Option<String>,
+    api_key: Option<String>,
+    form_id: Option<String>,
+    questions: Option<QuestionIds>,
+}
+
+/// The `[file]` table, read with `backend = "file"` or `"memory"`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FileTickets {
+    path: Option<PathBuf>,
+}
+
+/// The `[report]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FileReport {
+    templates: Option<PathBuf>,
+    dir: Option<PathBuf>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("could not read config file {}: {source}", path.display())]
+    Read { path: PathBuf, source: io::Error },
+    #[error("could not parse config file {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("invalid base URL {url:?} (from {origin}): {reason}")]
+    InvalidBaseUrl {
+        url: String,
+        origin: String,
+        reason: String,
+    },
+    #[error("{name} must be set to use backend = {backend:?}")]
+    Missing {
+        name: &'static str,
+        backend: &'static str,
+    },
+    #[error("invalid value {value:?} for {name}: {reason}")]
+    InvalidValue {
+        name: String,
+        value: String,
+        reason: String,
+    },
+}
+
+impl Config {
+    pub fn load(cli: &Cli) -> Result<Self, ConfigError> {
+        let explicit_path = cli
+            .config
+            .clone()
+            .or_else(|| env::var_os(CONFIG_PATH_ENV).map(PathBuf::from));
+
+        let (path, file) = match explicit_path {
+            // A path the user asked for must exist.
+            Some(path) => {
+                let file = read_file_config(&path)?;
+                (Some(path), file)
+            }
+            // The default location is optional.
+            None => match default_path() {
+                Some(path) if path.exists() => {
+                    let file = read_file_config(&path)?;
+                    (Some(path), file)
+                }
+                _ => (None, FileConfig::default()),
+            },
+        };
+
+        let env = |name: &str| env::var(name).ok().filter(|s| !s.is_empty());
+        Self::resolve(path.as_deref(), file, env, cli)
+    }
+
+    fn resolve(
+        path: Option<&Path>,
+        file: FileConfig,
+        env: impl Fn(&str) -> Option<String>,
+        cli: &Cli,
+    ) -> Result<Self, ConfigError> {
+        let file_origin = path
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "config file".to_string());
+
+        let backend = match file.backend.as_deref().map(str::trim) {
+            None | Some("rest") => Backend::Rest,
+            Some("jotform") => {
+                let api_key = env(JOTFORM_API_KEY_ENV);
+                Backend::Jotform(Box::new(jotform_config(
+                    file.jotform,
+                    api_key,
+                    &file_origin,
+                )?))
+            }
+            Some("file") => Backend::File(relative_to_config(path, file.file.path).ok_or(
+                ConfigError::Missing {
+                    name: "file.path",
+                    backend: "file",
+                },
+            )?),
+            Some("memory") => Backend::Memory(relative_to_config(path, file.file.path)),
+            Some(other) => {
+                return Err(ConfigError::InvalidValue {
+                    name: "backend".to_string(),
+                    value: other.to_string(),
+                    reason: "expected \"rest\", \"jotform\", \"file\" or \"memory\"".to_string(),
+                })
+            }
+        };
+
+        let (raw, origin) = if let Some(url) = cli.base_url.clone() {
+            (url, "--base-url".to_string())
+        } else if let Some(url) = env(BASE_URL_ENV) {
+            (url, BASE_URL_ENV.to_string())
+        } else if let Some(url) = file.base_url {
+            (url, file_origin)
+        } else {
+            (DEFAULT_BASE_URL.to_string(), "default".to_string())
+        };
+
+        let refresh_secs = match (cli.refresh, env(REFRESH_SECS_ENV)) {
+            (Some(secs), _) => Some(secs),
+            (None, Some(value)) => {
+                Some(
+                    value
+                        .trim()
+                        .parse::<u64>()
+                        .map_err(|e| ConfigError::InvalidValue {
+                            name: REFRESH_SECS_ENV.to_string(),
+                            value: value.clone(),
+                            reason: e.to_string(),
+                        })?,
+                )
+            }
+            (None, None) => file.refresh_secs,
+        };
+
+        let user = cli
+            .user
+            .clone()
+            .or_else(|| env(USER_ENV))
+            .or(file.user)
+            .map(|user| user.trim().to_string())
+            .filter(|user| !user.is_empty());
+
+        Ok(Config {
+            path: path.map(Path::to_path_buf),
+            base_url: parse_base_url(&raw, &origin)?,
+            backend,
+            refresh_interval: refresh_secs
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+            confirm_high_priority_close: file.confirm_high_priority_close.unwrap_or(true),
+            user,
+            staff: file.staff,
+            report: ReportConfig {
+                // Next to the config file unless it says otherwise.
+                templates: relative_to_config(path, file.report.templates).or_else(|| {
+                    path.map(Path::to_path_buf)
+                        .or_else(default_path)
+                        .and_then(|path| Some(path.parent()?.join("templates")))
+                }),
+                dir: relative_to_config(path, file.report.dir),
+            },
+            overrides: cli.clone(),
+        })
+    }
+
+    /// Built-in defaults with an empty in-memory backend, ignoring any
+    /// config file or environment.
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        let mut config = Self::resolve(None, FileConfig::default(), |_| None, &Cli::default())
+            .expect("defaults are valid");
+        config.backend = Backend::Memory(None);
+        config
+    }
+
+    /// Identifies a remote source of tickets, so each gets its own offline
+    /// cache. Local backends have nothing to cache.
+    pub fn source_url(&self) -> Option<Url> {
+        match &self.backend {
+            Backend::Rest => Some(self.base_url.clone()),
+            Backend::Jotform(jotform) => jotform
+                .api_url
+                .join(&format!("form/{}/", jotform.form_id))
+                .ok(),
+            Backend::File(_) | Backend::Memory(_) => None,
+        }
+    }
+
+    /// Re-reads every source with the same command-line flags.
+    pub fn reload(&self) -> Result<Self, ConfigError> {
+        Config::load(&self.overrides)
+    }
+
+    /// The file a user should edit: the one in use, else wherever `load`
+    /// would look for one.
+    pub fn edit_path(&self) -> Option<PathBuf> {
+        self.path
+            .clone()
+            .or_else(|| self.overrides.config.clone())
+            .or_else(|| env::var_os(CONFIG_PATH_ENV).map(PathBuf::from))
+            .or_else(default_path)
+    }
+}
+
+/// Written to a new config file opened from the connection error screen.
+pub const TEMPLATE: &str = r#"# jotview configuration
+
+# Base URL of the jotforms backend.
+# base_url = "http://localhost:3030"
+
+# "rest" for the server at base_url, or "jotform" to read the submissions of
+# a form on jotform.com directly, set up in the [jotform] table below.
+# backend = "rest"
+#
+# "file" reads and writes the tickets in [file] path, a JSON or YAML list shaped
+# like the server's /jotforms; "memory" starts from that file if set, and
+# never saves.
+
+# Re-fetch the list in the background every N seconds (0 disables).
+# refresh_secs = 60
+
+# Ask for confirmation before closing a High-priority ticket.
+# confirm_high_priority_close = true
+
+# Your name as it appears in the assignee field, for the "mine" filter.
+# user = "Ann Lee"
+
+# People tickets can be assigned to. Leave unset to fetch them from /staff.
+# staff = ["Ann Lee", "Bo Smith"]
+
+# [file]
+# path = "tickets.yaml"
+
+# Printable work orders (p and P in the table, or `jotview work-order`).
+# [report]
+# Directory of work_order.html / work_order.txt templates replacing the
+# built-in ones; defaults to "templates" next to this file.
+# templates = "templates"
+# Where reports are written; defaults to the current directory. Relative
+# paths here, like [file] path, start from this file's directory.
+# dir = "work-orders"
+
+# [jotform]
+# api_url = "https://api.jotform.com"
+# The key can also come from $JOTVIEW_JOTFORM_API_KEY.
+# api_key = "..."
+# form_id = "241234567890123"
+#
+# Question ID holding each field, from the form builder's question properties.
+# [jotform.questions]
+# submitter_name = "3"
+# location = "4"
+# exhibit_name = "5"
+# description = "6"
+# priority_level = "7"
+# department = "8"
+# status = "9"
+# assignee = "10"
+"#;
+
+/// `$XDG_CONFIG_HOME/jotview/config.toml`, or the platform equivalent.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("jotview").join("config.toml"))
+}
+
+fn read_file_config(path: &Path) -> Result<FileConfig, ConfigError> {
+    let contents = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    toml::from_str(&contents).map_err(|source| ConfigError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// A path set in the config file, relative to the file's directory.
+fn relative_to_config(config_path: Option<&Path>, path: Option<PathBuf>) -> Option<PathBuf> {
+    let path = path.filter(|path| !path.as_os_str().is_empty())?;
+    Some(match config_path.and_then(Path::parent) {
+        Some(dir) => dir.join(path),
+        None => path,
+    })
+}
+
+/// The `[jotform]` table with every required key present. An API key from
+/// the environment wins over the file's.
+fn jotform_config(
+    file: FileJotform,
+    api_key: Option<String>,
+    origin: &str,
+) -> Result<JotformConfig, ConfigError> {
+    let missing = |name| ConfigError::Missing {
+        name,
+        backend: "jotform",
+    };
+    let api_url = file
+        .api_url
+        .unwrap_or_else(|| jotform::DEFAULT_API_URL.to_string());
+    Ok(JotformConfig {
+        api_url: parse_base_url(&api_url, origin)?,
+        api_key: api_key
+            .or(file.api_key)
+            .filter(|key| !key.trim().is_empty())
+            .ok_or_else(|| missing("jotform.api_key"))?,
+        form_id: file
+            .form_id
+            .filter(|id| !id.trim().is_empty())
+            .ok_or_else(|| missing("jotform.form_id"))?,
+        questions: file.questions.ok_or_else(|| missing("jotform.questions"))?,
+    })
+}
+
+/// Validates a backend URL and normalises it so that relative endpoint
+/// paths can be joined onto it.
+fn parse_base_url(raw: &str, origin: &str) -> Result<Url, ConfigError> {
+    let invalid = |reason: String| ConfigError::InvalidBaseUrl {
+        url: raw.to_string(),
+        origin: origin.to_string(),
+        reason,
+    };
+
+    let mut url = Url::parse(raw.trim()).map_err(|e| invalid(e.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid(format!(
+            "scheme must be http or https, not {:?}",
+            url.scheme()
+        )));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(invalid("missing host".to_string()));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(invalid("must not contain a query or fragment".to_string()));
+    }
+    // `Url::join` replaces the last path segment unless the path ends in `/`.
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve(file: FileConfig, env: &[(&str, &str)], cli: Cli) -> Result<Config, ConfigError> {
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Config::resolve(None, file, |name| env.get(name).cloned(), &cli)
+    }
+
+    fn cli_base_url(url: &str) -> Cli {
+        Cli {
+            base_url: Some(url.to_string()),
+            ..Cli::default()
+        }
+    }
+
+    #[test]
+    fn defaults_to_localhost() {
+        let config = resolve(FileConfig::default(), &[], Cli::default()).unwrap();
+        assert_eq!(config.base_url.as_str(), "http://localhost:3030/");
+        assert_eq!(config.refresh_interval, None);
+    }
+
+    #[test]
+    fn later_sources_override_earlier_ones() {
+        let file = || FileConfig {
+            base_url: Some("http://file:1".to_string()),
+            ..FileConfig::default()
+        };
+        let env = [(BASE_URL_ENV, "http://env:2")];
+
+        let config = resolve(file(), &env, Cli::default()).unwrap();
+        assert_eq!(config.base_url.as_str(), "http://env:2/");
+
+        let config = resolve(file(), &env, cli_base_url("https://cli:3")).unwrap();
+        assert_eq!(config.base_url.as_str(), "https://cli:3/");
+    }
+
+    #[test]
+    fn base_path_is_kept_for_joins() {
+        let config = resolve(
+            FileConfig::default(),
+            &[],
+            cli_base_url("https://example.com/api"),
+        )
+        .unwrap();
+        assert_eq!(
+            config.base_url.join("jotforms").unwrap().as_str(),
+            "https://example.com/api/jotforms"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_urls() {
+        for bad in [
+            "localhost:3030",
+            "ftp://example.com",
+            "not a url",
+            "http://x/?a=1",
+        ] {
+            let err = resolve(FileConfig::default(), &[], cli_base_url(bad)).unwrap_err();
+            assert!(
+                matches!(err, ConfigError::InvalidBaseUrl { ref origin, .. } if origin == "--base-url"),
+                "{bad}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn refresh_interval_zero_disables_and_env_is_validated() {
+        let file = FileConfig {
+            refresh_secs: Some(30),
+            ..FileConfig::default()
+        };
+        let config = resolve(file, &[(REFRESH_SECS_ENV, "0")], Cli::default()).unwrap();
+        assert_eq!(config.refresh_interval, None);
+
+        let err = resolve(
+            FileConfig::default(),
+            &[(REFRESH_SECS_ENV, "soon")],
+            Cli::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidValue { .. }));
+    }
+
+    #[test]
+    fn user_is_layered_and_blank_means_unset() {
+        let file = || FileConfig {
+            user: Some("Ann Lee".to_string()),
+            ..FileConfig::default()
+        };
+        let config = resolve(file(), &[], Cli::default()).unwrap();
+        assert_eq!(config.user.as_deref(), Some("Ann Lee"));
+
+        let config = resolve(file(), &[(USER_ENV, " Bo Smith ")], Cli::default()).unwrap();
+        assert_eq!(config.user.as_deref(), Some("Bo Smith"));
+
+        let cli = Cli {
+            user: Some(String::new()),
+            ..Cli::default()
+        };
+        assert_eq!(resolve(file(), &[], cli).unwrap().user, None);
+    }
+
+    #[test]
+    fn jotform_backend_needs_its_keys_and_takes_the_api_key_from_env() {
+        let file = || FileConfig {
+            backend: Some("jotform".to_string()),
+            jotform: toml::from_str(
+                r#"
+                form_id = "42"
+                [questions]
+                submitter_name = "3"
+                location = "4"
+                exhibit_name = "5"
+                description = "6"
+                priority_level = "7"
+                department = "8"
+                status = "9"
+                "#,
+            )
+            .unwrap(),
+            ..FileConfig::default()
+        };
+        let err = resolve(file(), &[], Cli::default()).unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::Missing {
+                name: "jotform.api_key",
+                ..
+            }
+        ));
+
+        let config = resolve(file(), &[(JOTFORM_API_KEY_ENV, "secret")], Cli::default()).unwrap();
+        let Backend::Jotform(jotform) = &config.backend else {
+            panic!("expected the jotform backend");
+        };
+        assert_eq!(jotform.api_key, "secret");
+        assert_eq!(
+            config.source_url().unwrap().as_str(),
+            "https://api.jotform.com/form/42/"
+        );
+    }
+}
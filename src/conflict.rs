@@ -0,0 +1,237 @@
+use crate::{
+    model::{Jotform, JotformPatch},
+    outbox::Change,
+};
+
+/// A field two copies of a ticket can disagree on, in dialog order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictField {
+    Status,
+    Priority,
+    Department,
+    Location,
+    Exhibit,
+    Assignee,
+}
+
+/// How the user settles a conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Overwrite the server copy with every field of ours.
+    KeepMine,
+    /// Drop our change.
+    TakeTheirs,
+    /// Send just the fields our change set, on top of the server copy.
+    Reapply,
+}
+
+/// A save that lost a race: someone else changed the ticket after we read it.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    /// The ticket as we last saw it, before our change.
+    pub base: Jotform,
+    /// What our change would have made of it.
+    pub mine: Jotform,
+    /// The ticket as the backend has it now.
+    pub theirs: Jotform,
+}
+
+impl ConflictField {
+    pub const ALL: [ConflictField; 6] = [
+        ConflictField::Status,
+        ConflictField::Priority,
+        ConflictField::Department,
+        ConflictField::Location,
+        ConflictField::Exhibit,
+        ConflictField::Assignee,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConflictField::Status => "Status",
+            ConflictField::Priority => "Priority",
+            ConflictField::Department => "Department",
+            ConflictField::Location => "Location",
+            ConflictField::Exhibit => "Exhibit",
+            ConflictField::Assignee => "Assignee",
+        }
+    }
+
+    pub fn value(self, jotform: &Jotform) -> String {
+        match self {
+            ConflictField::Status => jotform.status.to_string(),
+            ConflictField::Priority => jotform.priority_level.to_string(),
+            ConflictField::Department => jotform.department.to_string(),
+            ConflictField::Location => jotform.location.clone(),
+            ConflictField::Exhibit => jotform.exhibit_name.clone(),
+            ConflictField::Assignee => jotform
+                .assignee
+                .clone()
+                .unwrap_or_else(|| "Unassigned".to_string()),
+        }
+    }
+
+    fn differs(self, a: &Jotform, b: &Jotform) -> bool {
+        match self {
+            ConflictField::Status => a.status != b.status,
+            ConflictField::Priority => a.priority_level != b.priority_level,
+            ConflictField::Department => a.department != b.department,
+            ConflictField::Location => a.location != b.location,
+            ConflictField::Exhibit => a.exhibit_name != b.exhibit_name,
+            ConflictField::Assignee => a.assignee != b.assignee,
+        }
+    }
+}
+
+impl Conflict {
+    pub fn new(base: Jotform, change: &Change, theirs: Jotform) -> Self {
+        let mut mine = base.clone();
+        change.apply_to(&mut mine);
+        Self { base, mine, theirs }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.theirs.id
+    }
+
+    /// Whether our change set `field`.
+    pub fn touched(&self, field: ConflictField) -> bool {
+        field.differs(&self.base, &self.mine)
+    }
+
+    /// Whether someone else changed a field our change sets too. If not, the
+    /// change can go on top of theirs without losing anything.
+    pub fn overlaps(&self) -> bool {
+        ConflictField::ALL
+            .into_iter()
+            .any(|field| self.touched(field) && field.differs(&self.base, &self.theirs))
+    }
+
+    /// Fields to show: the ones our change set and any the two copies
+    /// disagree on.
+    pub fn fields(&self) -> Vec<ConflictField> {
+        ConflictField::ALL
+            .into_iter()
+            .filter(|&field| self.touched(field) || field.differs(&self.mine, &self.theirs))
+            .collect()
+    }
+
+    /// What to send, against the server copy, to settle the conflict.
+    pub fn changes(&self, resolution: Resolution) -> Vec<Change> {
+        let fields = ConflictField::ALL.into_iter().filter(|&field| {
+            field.differs(&self.mine, &self.theirs)
+                && match resolution {
+                    Resolution::KeepMine => true,
+                    Resolution::TakeTheirs => false,
+                    Resolution::Reapply => self.touched(field),
+                }
+        });
+
+        let mut changes = Vec::new();
+        let mut patch = JotformPatch::default();
+        for field in fields {
+            let mine = &self.mine;
+            match field {
+                ConflictField::Status => changes.push(Change::Status {
+                    status: mine.status.clone(),
+                }),
+                ConflictField::Priority => patch.priority_level = Some(mine.priority_level.clone()),
+                ConflictField::Department => patch.department = Some(mine.department.clone()),
+                ConflictField::Location => patch.location = Some(mine.location.clone()),
+                ConflictField::Exhibit => patch.exhibit_name = Some(mine.exhibit_name.clone()),
+                ConflictField::Assignee => patch.assignee = Some(mine.assignee.clone()),
+            }
+        }
+        if !patch.is_empty() {
+            changes.push(Change::Fields { patch });
+        }
+        changes
+    }
+
+    /// The ticket once `resolution` has been saved.
+    pub fn resolved(&self, resolution: Resolution) -> Jotform {
+        let mut jotform = self.theirs.clone();
+        for change in self.changes(resolution) {
+            change.apply_to(&mut jotform);
+        }
+        jotform
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        model::{Priority, Status},
+        test_support::jotform,
+    };
+
+    #[test]
+    fn resolutions_send_all_of_mine_only_my_change_or_nothing() {
+        let mut theirs = jotform("7");
+        theirs.status = Status::Closed;
+        theirs.priority_level = Priority::High;
+        theirs.updated_at = Some("2".to_string());
+        let conflict = Conflict::new(
+            jotform("7"),
+            &Change::Status {
+                status: Status::InProgress,
+            },
+            theirs,
+        );
+        assert!(conflict.overlaps());
+        assert_eq!(
+            conflict.fields(),
+            [ConflictField::Status, ConflictField::Priority]
+        );
+
+        assert_eq!(
+            conflict.changes(Resolution::KeepMine),
+            [
+                Change::Status {
+                    status: Status::InProgress
+                },
+                Change::Fields {
+                    patch: JotformPatch {
+                        priority_level: Some(Priority::Low),
+                        ..JotformPatch::default()
+                    }
+                },
+            ]
+        );
+        let reapplied = conflict.resolved(Resolution::Reapply);
+        assert_eq!(reapplied.status, Status::InProgress);
+        assert_eq!(reapplied.priority_level, Priority::High);
+        assert!(conflict.changes(Resolution::TakeTheirs).is_empty());
+    }
+
+    #[test]
+    fn changes_to_other_fields_do_not_overlap() {
+        let mut theirs = jotform("7");
+        theirs.status = Status::Closed;
+        theirs.updated_at = Some("2".to_string());
+        let conflict = Conflict::new(
+            jotform("7"),
+            &Change::Fields {
+                patch: JotformPatch {
+                    location: Some("Atrium".to_string()),
+                    ..JotformPatch::default()
+                },
+            },
+            theirs.clone(),
+        );
+        assert!(!conflict.overlaps());
+
+        let mut base = jotform("7");
+        base.location = "Atrium".to_string();
+        theirs.location = "Cafe".to_string();
+        let conflict = Conflict::new(
+            base,
+            &Change::Status {
+                status: Status::Open,
+            },
+            theirs,
+        );
+        assert!(!conflict.overlaps());
+    }
+}
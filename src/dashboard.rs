@@ -0,0 +1,197 @@
+//! Workload figures for the dashboard tab, worked out from the tickets
+//! already loaded.
+
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+use crate::model::{Department, Jotform, Priority, Status};
+
+/// Days of submissions charted, today included.
+pub const DAYS: usize = 30;
+/// Locations and exhibits listed.
+pub const TOP: usize = 5;
+
+/// Open-ticket age buckets: label and the oldest age, in whole days, each
+/// one takes. The last has no upper limit.
+const AGE_BUCKETS: [(&str, i64); 5] = [
+    ("Under a day", 0),
+    ("1–3 days", 3),
+    ("4–7 days", 7),
+    ("8–30 days", 30),
+    ("Older", i64::MAX),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// Every known value, even at zero, then any others the backend sent.
+    pub by_status: Vec<(Status, u64)>,
+    pub by_priority: Vec<(Priority, u64)>,
+    pub by_department: Vec<(Department, u64)>,
+    /// Open and in-progress tickets by time since submission, youngest
+    /// first. Tickets with an unreadable date are counted as "Undated".
+    pub open_by_age: Vec<(&'static str, u64)>,
+    /// Most tickets first, ties by name; blank names are left out.
+    pub top_locations: Vec<(String, u64)>,
+    pub top_exhibits: Vec<(String, u64)>,
+    /// Submissions on each of the last [`DAYS`] days, oldest first.
+    pub per_day: Vec<(NaiveDate, u64)>,
+}
+
+impl Stats {
+    pub fn new(jotforms: &[Jotform], now: NaiveDateTime) -> Self {
+        let today = now.date();
+        let mut open_by_age: Vec<(&str, u64)> =
+            AGE_BUCKETS.iter().map(|&(label, _)| (label, 0)).collect();
+        let mut undated = 0;
+        for jotform in jotforms.iter().filter(|j| j.status.is_open()) {
+            let Some(submitted) = jotform.created_at.timestamp() else {
+                undated += 1;
+                continue;
+            };
+            // A clock running ahead of ours makes for a negative age.
+            let days = now.signed_duration_since(submitted).num_days().max(0);
+            let bucket = AGE_BUCKETS
+                .iter()
+                .position(|&(_, max)| days <= max)
+                .expect("the last bucket has no limit");
+            open_by_age[bucket].1 += 1;
+        }
+        if undated > 0 {
+            open_by_age.push(("Undated", undated));
+        }
+
+        let first_day = today - TimeDelta::days(DAYS as i64 - 1);
+        let mut per_day: Vec<(NaiveDate, u64)> = first_day
+            .iter_days()
+            .take(DAYS)
+            .map(|day| (day, 0))
+            .collect();
+        for date in jotforms
+            .iter()
+            .filter_map(|j| j.created_at.parsed_date().ok())
+        {
+            if let Some(day) = per_day.iter_mut().find(|(day, _)| *day == date) {
+                day.1 += 1;
+            }
+        }
+
+        Self {
+            by_status: tally(Status::KNOWN, jotforms.iter().map(|j| &j.status)),
+            by_priority: tally(Priority::KNOWN, jotforms.iter().map(|j| &j.priority_level)),
+            by_department: tally(Department::KNOWN, jotforms.iter().map(|j| &j.department)),
+            open_by_age,
+            top_locations: top(jotforms.iter().map(|j| j.location.as_str())),
+            top_exhibits: top(jotforms.iter().map(|j| j.exhibit_name.as_str())),
+            per_day,
+        }
+    }
+}
+
+fn tally<'a, T: Clone + PartialEq + 'a>(
+    known: &[T],
+    values: impl Iterator<Item = &'a T>,
+) -> Vec<(T, u64)> {
+    let mut counts: Vec<(T, u64)> = known.iter().map(|value| (value.clone(), 0)).collect();
+    for value in values {
+        match counts.iter_mut().find(|(counted, _)| counted == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((value.clone(), 1)),
+        }
+    }
+    counts
+}
+
+/// The [`TOP`] most frequent names, compared ignoring case and surrounding
+/// blanks; each is shown as first seen.
+fn top<'a>(names: impl Iterator<Item = &'a str>) -> Vec<(String, u64)> {
+    let mut counts: Vec<(String, u64)> = Vec::new();
+    for name in names.map(str::trim).filter(|name| !name.is_empty()) {
+        match counts
+            .iter_mut()
+            .find(|(counted, _)| counted.eq_ignore_ascii_case(name))
+        {
+            Some((_, count)) => *count += 1,
+            None => counts.push((name.to_string(), 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(TOP);
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{jotform, submitted};
+
+    #[test]
+    fn counts_ages_and_days() {
+        let now = NaiveDate::from_ymd_opt(2024, 5, 31)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let ticket = |date: &str, location: &str, status: Status| Jotform {
+            created_at: submitted(date),
+            location: location.to_string(),
+            exhibit_name: String::new(),
+            department: Department::Unknown("Security".to_string()),
+            status,
+            ..jotform("1")
+        };
+        let jotforms = [
+            ticket("2024-05-31", "Lobby", Status::Open),
+            ticket("2024-05-29", "lobby ", Status::InProgress),
+            ticket("2024-05-20", "Atrium", Status::Open),
+            ticket("2024-03-01", "Cafe", Status::Open),
+            ticket("2024-05-30", "Atrium", Status::Closed),
+            ticket("someday", "Lobby", Status::Open),
+        ];
+        let stats = Stats::new(&jotforms, now);
+
+        assert_eq!(
+            stats.by_status,
+            [
+                (Status::Open, 4),
+                (Status::InProgress, 1),
+                (Status::Closed, 1),
+                (Status::Unplanned, 0)
+            ]
+        );
+        assert_eq!(
+            stats.by_department,
+            [
+                (Department::Exhibits, 0),
+                (Department::Operations, 0),
+                (Department::Unknown("Security".to_string()), 6)
+            ]
+        );
+        assert_eq!(
+            stats.open_by_age,
+            [
+                ("Under a day", 1),
+                ("1–3 days", 1),
+                ("4–7 days", 0),
+                ("8–30 days", 1),
+                ("Older", 1),
+                ("Undated", 1)
+            ]
+        );
+        assert_eq!(
+            stats.top_locations,
+            [
+                ("Lobby".to_string(), 3),
+                ("Atrium".to_string(), 2),
+                ("Cafe".to_string(), 1)
+            ]
+        );
+        assert!(stats.top_exhibits.is_empty());
+
+        assert_eq!(stats.per_day.len(), DAYS);
+        assert_eq!(
+            stats.per_day.first().unwrap().0,
+            NaiveDate::from_ymd_opt(2024, 5, 2).unwrap()
+        );
+        let counts: Vec<u64> = stats.per_day.iter().map(|(_, n)| *n).collect();
+        assert_eq!(counts[DAYS - 3..], [1, 1, 1]);
+        assert_eq!(counts.iter().sum::<u64>(), 4);
+    }
+}
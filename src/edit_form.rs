@@ -0,0 +1,190 @@
+use crate::model::{Department, Jotform, JotformPatch, Priority};
+
+/// Longest location or exhibit name the form accepts.
+const MAX_TEXT_LEN: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditField {
+    Priority,
+    Department,
+    Location,
+    Exhibit,
+}
+
+impl EditField {
+    pub const ALL: [EditField; 4] = [
+        EditField::Priority,
+        EditField::Department,
+        EditField::Location,
+        EditField::Exhibit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EditField::Priority => "Priority",
+            EditField::Department => "Department",
+            EditField::Location => "Location",
+            EditField::Exhibit => "Exhibit",
+        }
+    }
+
+    /// Picked from a fixed list rather than typed.
+    pub fn is_choice(self) -> bool {
+        matches!(self, EditField::Priority | EditField::Department)
+    }
+}
+
+/// Overlay for reclassifying the selected jotform, opened with `c`.
+///
+/// Holds a working copy of the editable fields; only the ones that differ
+/// from the row as it was when the form opened are sent.
+#[derive(Debug, Clone)]
+pub struct EditForm {
+    pub original: Jotform,
+    pub priority: Priority,
+    pub department: Department,
+    pub location: String,
+    pub exhibit_name: String,
+    /// Index into `EditField::ALL`.
+    pub focus: usize,
+    pub error: Option<String>,
+}
+
+impl EditForm {
+    pub fn new(jotform: &Jotform) -> Self {
+        Self {
+            original: jotform.clone(),
+            priority: jotform.priority_level.clone(),
+            department: jotform.department.clone(),
+            location: jotform.location.clone(),
+            exhibit_name: jotform.exhibit_name.clone(),
+            focus: 0,
+            error: None,
+        }
+    }
+
+    pub fn focused(&self) -> EditField {
+        EditField::ALL[self.focus]
+    }
+
+    pub fn move_focus(&mut self, delta: isize) {
+        let len = EditField::ALL.len() as isize;
+        self.focus = (self.focus as isize + delta).rem_euclid(len) as usize;
+    }
+
+    /// Steps a choice field through its known values.
+    pub fn cycle_choice(&mut self, delta: isize) {
+        match self.focused() {
+            EditField::Priority => self.priority = cycle(Priority::KNOWN, &self.priority, delta),
+            EditField::Department => {
+                self.department = cycle(Department::KNOWN, &self.department, delta);
+            }
+            EditField::Location | EditField::Exhibit => {}
+        }
+    }
+
+    /// The focused text field, if it is one.
+    pub fn focused_text(&mut self) -> Option<&mut String> {
+        match self.focused() {
+            EditField::Location => Some(&mut self.location),
+            EditField::Exhibit => Some(&mut self.exhibit_name),
+            EditField::Priority | EditField::Department => None,
+        }
+    }
+
+    pub fn value(&self, field: EditField) -> &str {
+        match field {
+            EditField::Priority => self.priority.as_str(),
+            EditField::Department => self.department.as_str(),
+            EditField::Location => &self.location,
+            EditField::Exhibit => &self.exhibit_name,
+        }
+    }
+
+    pub fn is_dirty(&self, field: EditField) -> bool {
+        match field {
+            EditField::Priority => self.priority != self.original.priority_level,
+            EditField::Department => self.department != self.original.department,
+            EditField::Location => self.location.trim() != self.original.location,
+            EditField::Exhibit => self.exhibit_name.trim() != self.original.exhibit_name,
+        }
+    }
+
+    /// The changed fields, or why the form can't be saved.
+    pub fn patch(&self) -> Result<JotformPatch, String> {
+        for field in [EditField::Location, EditField::Exhibit] {
+            let value = self.value(field).trim();
+            if value.is_empty() {
+                return Err(format!("{} can't be empty", field.label()));
+            }
+            if value.chars().count() > MAX_TEXT_LEN {
+                return Err(format!(
+                    "{} must be at most {} characters",
+                    field.label(),
+                    MAX_TEXT_LEN
+                ));
+            }
+        }
+
+        let changed = |field| self.is_dirty(field);
+        Ok(JotformPatch {
+            location: changed(EditField::Location).then(|| self.location.trim().to_string()),
+            exhibit_name: changed(EditField::Exhibit).then(|| self.exhibit_name.trim().to_string()),
+            priority_level: changed(EditField::Priority).then(|| self.priority.clone()),
+            department: changed(EditField::Department).then(|| self.department.clone()),
+            assignee: None,
+        })
+    }
+}
+
+/// The known value `delta` steps from `current`; unknown values start over
+/// at either end.
+pub fn cycle<T: Clone + PartialEq>(known: &[T], current: &T, delta: isize) -> T {
+    let len = known.len() as isize;
+    let next = match known.iter().position(|k| k == current) {
+        Some(i) => (i as isize + delta).rem_euclid(len),
+        None if delta < 0 => len - 1,
+        None => 0,
+    };
+    known[next as usize].clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::jotform;
+
+    #[test]
+    fn patch_contains_only_changed_fields() {
+        let mut form = EditForm::new(&jotform("1"));
+        assert!(form.patch().unwrap().is_empty());
+
+        form.cycle_choice(1);
+        form.location = " Lobby ".to_string();
+        form.exhibit_name = "Fossils ".to_string();
+        assert!(!form.is_dirty(EditField::Location));
+
+        let patch = form.patch().unwrap();
+        assert_eq!(
+            serde_json::to_value(&patch).unwrap(),
+            serde_json::json!({ "priority_level": "Medium", "exhibit_name": "Fossils" })
+        );
+
+        let mut updated = jotform("1");
+        patch.apply_to(&mut updated);
+        assert_eq!(updated.priority_level, Priority::Medium);
+        assert_eq!(updated.exhibit_name, "Fossils");
+        assert_eq!(updated.location, "Lobby");
+    }
+
+    #[test]
+    fn rejects_blank_and_overlong_text() {
+        let mut form = EditForm::new(&jotform("1"));
+        form.location = "   ".to_string();
+        assert_eq!(form.patch().unwrap_err(), "Location can't be empty");
+
+        form.location = "Lobby".to_string();
+        form.exhibit_name = "x".repeat(MAX_TEXT_LEN + 1);
+        assert!(form.patch().is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use reqwest::StatusCode;
+
+use crate::model::Jotform;
+
+/// Everything that can go wrong talking to the backend or reading its data.
+#[derive(Debug, thiserror::Error)]
+pub enum JotviewError {
+    #[error("could not reach {url}: {source}")]
+    Network {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("{url} responded with {status}{}", body_suffix(.body))]
+    HttpStatus {
+        url: String,
+        status: StatusCode,
+        body: String,
+    },
+    /// The backend refused a save made against an outdated copy (409 or 412).
+    #[error("{url} refused the change: the ticket was changed by someone else")]
+    Conflict {
+        url: String,
+        /// The ticket as the backend has it now, if it could be fetched.
+        current: Option<Box<Jotform>>,
+    },
+    #[error("could not access {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("could not parse {path}: {reason}")]
+    Format { path: String, reason: String },
+    #[error("there is no ticket {id}")]
+    NotFound { id: String },
+    #[error("this backend does not support {what}")]
+    Unsupported { what: &'static str },
+    #[error("could not decode the response from {url}: {source}")]
+    Decode {
+        url: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("invalid date {value:?}: {source}")]
+    DateParse {
+        value: String,
+        #[source]
+        source: chrono::ParseError,
+    },
+}
+
+pub type Result<T, E = JotviewError> = std::result::Result<T, E>;
+
+/// Appends a short excerpt of an error response body, if there is one.
+fn body_suffix(body: &str) -> String {
+    const MAX_CHARS: usize = 200;
+    let body = body.trim();
+    if body.is_empty() {
+        return String::new();
+    }
+    let excerpt: String = body.chars().take(MAX_CHARS).collect();
+    let ellipsis = if body.chars().count() > MAX_CHARS {
+        "…"
+    } else {
+        ""
+    };
+    format!(": {}{}", excerpt, ellipsis)
+}
@@ -0,0 +1,317 @@
+//! Writing the table's rows to a file for spreadsheets and wiki pages: the
+//! `x` prompt in the TUI and `jotview export`.
+
+use chrono::NaiveDateTime;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::model::Jotform;
+
+/// The table's columns, left to right, followed by the description.
+pub const COLUMNS: [&str; 9] = [
+    "Submitter",
+    "Date",
+    "Location",
+    "Exhibit",
+    "Priority",
+    "Department",
+    "Status",
+    "Assignee",
+    "Description",
+];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Comma-separated, for spreadsheets
+    #[default]
+    Csv,
+    /// Pretty-printed, one object per ticket
+    Json,
+    /// A GitHub-style table
+    #[value(alias = "md")]
+    Markdown,
+}
+
+impl Format {
+    pub const ALL: [Format; 3] = [Format::Csv, Format::Json, Format::Markdown];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Format::Csv => "CSV",
+            Format::Json => "JSON",
+            Format::Markdown => "Markdown",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+            Format::Markdown => "md",
+        }
+    }
+
+    /// The next format, or the previous one going `back`, wrapping around.
+    pub fn cycle(self, back: bool) -> Format {
+        let index = Format::ALL.iter().position(|&f| f == self).unwrap_or(0);
+        let step = if back { Format::ALL.len() - 1 } else { 1 };
+        Format::ALL[(index + step) % Format::ALL.len()]
+    }
+
+    pub fn render(self, jotforms: &[&Jotform]) -> String {
+        match self {
+            Format::Csv => {
+                let rows = jotforms.iter().map(|j| cells(j).to_vec());
+                csv(std::iter::once(COLUMNS.map(String::from).to_vec()).chain(rows))
+            }
+            Format::Json => {
+                let rows: Vec<Row> = jotforms.iter().map(|j| Row::new(j)).collect();
+                serde_json::to_string_pretty(&rows).expect("tickets serialize") + "\n"
+            }
+            Format::Markdown => markdown(jotforms),
+        }
+    }
+}
+
+/// A ticket as the table shows it, with its description. Fields serialize
+/// in column order.
+#[derive(Serialize)]
+struct Row<'a> {
+    submitter: &'a str,
+    date: String,
+    location: &'a str,
+    exhibit: &'a str,
+    priority: &'a str,
+    department: &'a str,
+    status: &'a str,
+    assignee: Option<&'a str>,
+    description: &'a str,
+}
+
+impl<'a> Row<'a> {
+    fn new(jotform: &'a Jotform) -> Self {
+        Self {
+            submitter: &jotform.submitter_name.first,
+            date: date(jotform),
+            location: &jotform.location,
+            exhibit: &jotform.exhibit_name,
+            priority: jotform.priority_level.as_str(),
+            department: jotform.department.as_str(),
+            status: jotform.status.as_str(),
+            assignee: jotform.assignee.as_deref(),
+            description: &jotform.description,
+        }
+    }
+}
+
+/// The table's cells for `jotform`, as plain text, then its description.
+/// An unassigned ticket has an empty assignee.
+pub fn cells(jotform: &Jotform) -> [String; COLUMNS.len()] {
+    [
+        jotform.submitter_name.first.clone(),
+        date(jotform),
+        jotform.location.clone(),
+        jotform.exhibit_name.clone(),
+        jotform.priority_level.to_string(),
+        jotform.department.to_string(),
+        jotform.status.to_string(),
+        jotform.assignee.clone().unwrap_or_default(),
+        jotform.description.clone(),
+    ]
+}
+
+/// The submission date the way the table shows it.
+fn date(jotform: &Jotform) -> String {
+    jotform
+        .created_at
+        .parsed_date()
+        .map(|date| date.format("%m-%d-%Y").to_string())
+        .unwrap_or_else(|_| jotform.created_at.date.clone())
+}
+
+/// RFC 4180: fields holding a comma, quote or line break are quoted, with
+/// quotes doubled.
+pub fn csv(rows: impl IntoIterator<Item = Vec<String>>) -> String {
+    let mut text = String::new();
+    for row in rows {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|field| {
+                if field.contains([',', '"', '\n', '\r']) {
+                    format!("\"{}\"", field.replace('"', "\"\""))
+                } else {
+                    field.clone()
+                }
+            })
+            .collect();
+        text.push_str(&fields.join(","));
+        text.push_str("\r\n");
+    }
+    text
+}
+
+/// Pipes are escaped and line breaks become `<br>` so every ticket stays on
+/// one row.
+fn markdown(jotforms: &[&Jotform]) -> String {
+    let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+    let mut text = line(COLUMNS.map(String::from).to_vec());
+    text.push_str(&line(vec!["---".to_string(); COLUMNS.len()]));
+    for jotform in jotforms {
+        let cells = cells(jotform).map(|cell| {
+            cell.trim()
+                .replace('\\', "\\\\")
+                .replace('|', "\\|")
+                .replace("\r\n", "\n")
+                .replace(['\n', '\r'], "<br>")
+        });
+        text.push_str(&line(cells.to_vec()));
+    }
+    text
+}
+
+/// Where an export goes unless told otherwise, e.g.
+/// `jotview-20240501-093000.csv`.
+pub fn default_file_name(format: Format, now: NaiveDateTime) -> String {
+    format!(
+        "jotview-{}.{}",
+        now.format("%Y%m%d-%H%M%S"),
+        format.extension()
+    )
+}
+
+/// The save prompt opened with `x` over the table.
+#[derive(Debug, Clone)]
+pub struct ExportPrompt {
+    pub format: Format,
+    pub path: String,
+    pub error: Option<String>,
+    /// Set once Enter has been pressed on a path that already exists; the
+    /// next Enter replaces the file.
+    pub overwrite: bool,
+}
+
+impl ExportPrompt {
+    pub fn new(now: NaiveDateTime) -> Self {
+        let format = Format::default();
+        Self {
+            format,
+            path: default_file_name(format, now),
+            error: None,
+            overwrite: false,
+        }
+    }
+
+    /// Switches format, carrying the file extension along if the path still
+    /// has the old one.
+    pub fn cycle_format(&mut self, back: bool) {
+        let old = self.format;
+        self.format = old.cycle(back);
+        if let Some(stem) = self.path.strip_suffix(&format!(".{}", old.extension())) {
+            self.path = format!("{}.{}", stem, self.format.extension());
+        }
+        self.edited();
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.path.push(c);
+        self.edited();
+    }
+
+    pub fn pop(&mut self) {
+        self.path.pop();
+        self.edited();
+    }
+
+    fn edited(&mut self) {
+        self.error = None;
+        self.overwrite = false;
+    }
+
+    /// The path typed, with a leading `~/` expanded.
+    pub fn target(&self) -> Result<PathBuf, String> {
+        let path = self.path.trim();
+        if path.is_empty() {
+            return Err("Enter a file name".to_string());
+        }
+        match (path.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => Ok(home.join(rest)),
+            _ => Ok(PathBuf::from(path)),
+        }
+    }
+}
+
+/// Writes `jotforms` to `path` in `format`.
+pub fn write(path: &Path, format: Format, jotforms: &[&Jotform]) -> io::Result<()> {
+    fs::write(path, format.render(jotforms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::jotform;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn descriptions_are_escaped_for_each_format() {
+        let plain = Jotform {
+            assignee: Some("Bo".to_string()),
+            ..jotform("1")
+        };
+        let awkward = Jotform {
+            description: "Lamp out, again\nsays \"soon\" | maybe".to_string(),
+            ..plain.clone()
+        };
+        let rows = [&plain, &awkward];
+
+        assert_eq!(
+            Format::Csv.render(&rows),
+            "Submitter,Date,Location,Exhibit,Priority,Department,Status,Assignee,Description\r\n\
+             Ann,05-01-2024,Lobby,Dinosaurs,Low,Exhibits,Open,Bo,Lamp out\r\n\
+             Ann,05-01-2024,Lobby,Dinosaurs,Low,Exhibits,Open,Bo,\"Lamp out, again\nsays \"\"soon\"\" | maybe\"\r\n"
+        );
+        assert_eq!(
+            Format::Markdown.render(&rows).lines().nth(3),
+            Some(
+                "| Ann | 05-01-2024 | Lobby | Dinosaurs | Low | Exhibits | Open | Bo | \
+                 Lamp out, again<br>says \"soon\" \\| maybe |"
+            )
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&Format::Json.render(&rows)).unwrap();
+        assert_eq!(json[1]["description"], awkward.description.as_str());
+        assert_eq!(json[0]["date"], "05-01-2024");
+        let first = Format::Json.render(&rows[..1]);
+        let keys: Vec<&str> = first
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix('"')?.split_once('"'))
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, COLUMNS.map(str::to_lowercase).to_vec());
+    }
+
+    #[test]
+    fn prompt_suggests_a_timestamped_name_that_follows_the_format() {
+        let now = NaiveDate::from_ymd_opt(2024, 5, 1)
+            .unwrap()
+            .and_hms_opt(9, 30, 5)
+            .unwrap();
+        let mut prompt = ExportPrompt::new(now);
+        assert_eq!(prompt.path, "jotview-20240501-093005.csv");
+        prompt.cycle_format(false);
+        prompt.cycle_format(false);
+        assert_eq!(prompt.format, Format::Markdown);
+        assert_eq!(prompt.path, "jotview-20240501-093005.md");
+        prompt.cycle_format(true);
+        assert_eq!(prompt.path, "jotview-20240501-093005.json");
+
+        prompt.path = "open.txt".to_string();
+        prompt.cycle_format(false);
+        assert_eq!(prompt.path, "open.txt");
+        prompt.path.clear();
+        assert!(prompt.target().is_err());
+    }
+}
@@ -0,0 +1,320 @@
+use chrono::{Days, NaiveDate};
+use std::collections::HashSet;
+
+use crate::model::{Department, Jotform, Priority, Status, SubmissionDate};
+
+/// Structured filter over the loaded jotforms.
+///
+/// An empty set means "don't filter on this field"; otherwise a jotform must
+/// have one of the checked values.
+#[derive(Debug, Default)]
+pub struct Filter {
+    pub statuses: HashSet<Status>,
+    pub priorities: HashSet<Priority>,
+    pub departments: HashSet<Department>,
+    pub date_range: Option<DateRange>,
+    /// Only tickets assigned to this person; set by the "mine" quick filter.
+    pub assignee: Option<String>,
+}
+
+/// Inclusive range of submission dates; either end may be open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// The text the range was parsed from, shown back to the user.
+    pub input: String,
+}
+
+/// One line of the filter panel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterItem {
+    Status(Status),
+    Priority(Priority),
+    Department(Department),
+    DateRange,
+}
+
+/// UI state of the toggleable filter side panel.
+#[derive(Debug, Default)]
+pub struct FilterPanel {
+    pub open: bool,
+    pub cursor: usize,
+    /// Whether the date-range input line has focus.
+    pub editing_date: bool,
+    pub date_input: String,
+    pub date_error: Option<String>,
+}
+
+impl Filter {
+    pub fn is_active(&self) -> bool {
+        !self.statuses.is_empty()
+            || !self.priorities.is_empty()
+            || !self.departments.is_empty()
+            || self.date_range.is_some()
+            || self.assignee.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Filter::default();
+    }
+
+    pub fn matches(&self, jotform: &Jotform) -> bool {
+        (self.statuses.is_empty() || self.statuses.contains(&jotform.status))
+            && (self.priorities.is_empty() || self.priorities.contains(&jotform.priority_level))
+            && (self.departments.is_empty() || self.departments.contains(&jotform.department))
+            && self
+                .date_range
+                .as_ref()
+                .is_none_or(|range| range.contains(&jotform.created_at))
+            && self
+                .assignee
+                .as_ref()
+                .is_none_or(|assignee| jotform.assignee.as_ref() == Some(assignee))
+    }
+
+    pub fn is_checked(&self, item: &FilterItem) -> bool {
+        match item {
+            FilterItem::Status(status) => self.statuses.contains(status),
+            FilterItem::Priority(priority) => self.priorities.contains(priority),
+            FilterItem::Department(department) => self.departments.contains(department),
+            FilterItem::DateRange => self.date_range.is_some(),
+        }
+    }
+
+    pub fn toggle(&mut self, item: &FilterItem) {
+        fn flip<T: std::hash::Hash + Eq + Clone>(set: &mut HashSet<T>, value: &T) {
+            if !set.remove(value) {
+                set.insert(value.clone());
+            }
+        }
+        match item {
+            FilterItem::Status(status) => flip(&mut self.statuses, status),
+            FilterItem::Priority(priority) => flip(&mut self.priorities, priority),
+            FilterItem::Department(department) => flip(&mut self.departments, department),
+            FilterItem::DateRange => self.date_range = None,
+        }
+    }
+
+    /// Short description of the active filters for the table title,
+    /// e.g. `status: Open, InProgress · priority: High · date: 14d`.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        for (name, labels) in [
+            (
+                "status",
+                sorted_labels(&self.statuses, Status::KNOWN, Status::as_str),
+            ),
+            (
+                "priority",
+                sorted_labels(&self.priorities, Priority::KNOWN, Priority::as_str),
+            ),
+            (
+                "department",
+                sorted_labels(&self.departments, Department::KNOWN, Department::as_str),
+            ),
+        ] {
+            if !labels.is_empty() {
+                parts.push(format!("{}: {}", name, labels.join(", ")));
+            }
+        }
+        if let Some(range) = &self.date_range {
+            parts.push(format!("date: {}", range.input));
+        }
+        if let Some(assignee) = &self.assignee {
+            parts.push(format!("assignee: {}", assignee));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Labels of the values in `set`, known values first in declaration order.
+fn sorted_labels<'a, T: PartialEq>(
+    set: &'a HashSet<T>,
+    known: &[T],
+    label: fn(&T) -> &str,
+) -> Vec<&'a str> {
+    let mut values: Vec<&T> = set.iter().collect();
+    values.sort_by_key(|v| {
+        let rank = known.iter().position(|k| k == *v).unwrap_or(known.len());
+        (rank, label(v))
+    });
+    values.into_iter().map(label).collect()
+}
+
+impl DateRange {
+    /// Parses the date-range input line. Accepted forms:
+    ///
+    /// * `14d` — the last 14 days, including `today`
+    /// * `2024-05-01..2024-05-31`, `2024-05-01..`, `..2024-05-31`
+    /// * `2024-05-01` — that single day
+    pub fn parse(input: &str, today: NaiveDate) -> Result<Self, String> {
+        let input = input.trim();
+        let parse_date = |s: &str| {
+            NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+                .map_err(|_| format!("{:?} is not a YYYY-MM-DD date", s.trim()))
+        };
+        let optional_date = |s: &str| {
+            if s.trim().is_empty() {
+                Ok(None)
+            } else {
+                parse_date(s).map(Some)
+            }
+        };
+
+        let (from, to) = if let Some(days) = input.strip_suffix('d') {
+            let days: u64 = days
+                .trim()
+                .parse()
+                .ok()
+                .filter(|&d| d > 0)
+                .ok_or_else(|| format!("{:?} is not a positive number of days", days))?;
+            let from = today
+                .checked_sub_days(Days::new(days - 1))
+                .ok_or("date range is too large")?;
+            (Some(from), Some(today))
+        } else if let Some((from, to)) = input.split_once("..") {
+            (optional_date(from)?, optional_date(to)?)
+        } else {
+            let day = parse_date(input)?;
+            (Some(day), Some(day))
+        };
+
+        if from.is_none() && to.is_none() {
+            return Err("date range needs at least one end".to_string());
+        }
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                return Err(format!("{} is after {}", from, to));
+            }
+        }
+
+        Ok(DateRange {
+            from,
+            to,
+            input: input.to_string(),
+        })
+    }
+
+    /// Whether a submission date falls inside the range. Unparseable dates
+    /// never match.
+    pub fn contains(&self, submitted: &SubmissionDate) -> bool {
+        let Ok(date) = submitted.parsed_date() else {
+            return false;
+        };
+        self.from.is_none_or(|from| date >= from) && self.to.is_none_or(|to| date <= to)
+    }
+}
+
+/// Every panel line for the loaded jotforms: each distinct status, priority
+/// and department (known values first, in declaration order), then the date
+/// range input. Values that are currently checked stay listed even if no
+/// loaded jotform has them any more.
+pub fn items(jotforms: &[Jotform], filter: &Filter) -> Vec<FilterItem> {
+    fn distinct<T: Clone + PartialEq>(
+        known: &[T],
+        present: impl Iterator<Item = T>,
+        checked: &HashSet<T>,
+        wrap: impl Fn(T) -> FilterItem,
+    ) -> Vec<FilterItem> {
+        let mut values: Vec<T> = Vec::new();
+        for value in present.chain(checked.iter().cloned()) {
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+        values.sort_by_key(|v| known.iter().position(|k| k == v).unwrap_or(known.len()));
+        values.into_iter().map(wrap).collect()
+    }
+
+    let mut items = distinct(
+        Status::KNOWN,
+        jotforms.iter().map(|j| j.status.clone()),
+        &filter.statuses,
+        FilterItem::Status,
+    );
+    items.extend(distinct(
+        Priority::KNOWN,
+        jotforms.iter().map(|j| j.priority_level.clone()),
+        &filter.priorities,
+        FilterItem::Priority,
+    ));
+    items.extend(distinct(
+        Department::KNOWN,
+        jotforms.iter().map(|j| j.department.clone()),
+        &filter.departments,
+        FilterItem::Department,
+    ));
+    items.push(FilterItem::DateRange);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn submitted(date: &str) -> SubmissionDate {
+        SubmissionDate {
+            date: date.to_string(),
+            time: String::new(),
+        }
+    }
+
+    #[test]
+    fn parses_relative_and_absolute_ranges() {
+        let today = day("2024-05-20");
+
+        let range = DateRange::parse("14d", today).unwrap();
+        assert_eq!(range.from, Some(day("2024-05-07")));
+        assert_eq!(range.to, Some(today));
+
+        let range = DateRange::parse("2024-05-01..", today).unwrap();
+        assert_eq!((range.from, range.to), (Some(day("2024-05-01")), None));
+
+        let range = DateRange::parse("..2024-05-01", today).unwrap();
+        assert_eq!((range.from, range.to), (None, Some(day("2024-05-01"))));
+
+        let range = DateRange::parse("2024-05-03", today).unwrap();
+        assert!(range.contains(&submitted("2024-05-03")));
+        assert!(!range.contains(&submitted("2024-05-04")));
+    }
+
+    #[test]
+    fn rejects_bad_ranges() {
+        let today = day("2024-05-20");
+        for bad in [
+            "",
+            "..",
+            "0d",
+            "soon",
+            "2024-05-10..2024-05-01",
+            "05/01/2024",
+        ] {
+            assert!(DateRange::parse(bad, today).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn unparseable_submission_dates_are_excluded() {
+        let range = DateRange::parse("2024-05-01..", day("2024-05-20")).unwrap();
+        assert!(!range.contains(&submitted("yesterday")));
+    }
+
+    #[test]
+    fn summary_lists_checked_values_in_known_order() {
+        let mut filter = Filter::default();
+        filter.toggle(&FilterItem::Status(Status::InProgress));
+        filter.toggle(&FilterItem::Status(Status::Open));
+        filter.toggle(&FilterItem::Priority(Priority::High));
+        filter.date_range = Some(DateRange::parse("14d", day("2024-05-20")).unwrap());
+        filter.assignee = Some("Ann Lee".to_string());
+        assert_eq!(
+            filter.summary(),
+            "status: Open, InProgress · priority: High · date: 14d · assignee: Ann Lee"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Drives [`App`] without a terminal or network, for tests: scripted keys
+//! go in, and the state and the commands it asked for come out.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{collections::VecDeque, sync::Arc};
+
+use crate::{
+    app::{App, Message},
+    backend::MemoryBackend,
+    command::Command,
+    config::Config,
+    model::Jotform,
+};
+
+pub struct Headless {
+    pub app: App,
+    /// What the app's commands run against.
+    pub backend: Arc<MemoryBackend>,
+    /// Every command the app has asked for, in order, whether or not it has
+    /// been run yet.
+    pub commands: Vec<Command>,
+    pending: VecDeque<Command>,
+}
+
+impl Headless {
+    /// An app that has just loaded `jotforms` from a backend holding them.
+    pub fn new(jotforms: Vec<Jotform>) -> Self {
+        let backend = Arc::new(MemoryBackend::new(jotforms.clone()));
+        let mut app = App::new(Config::in_memory(), backend.clone());
+        app.handle_message(Message::Loaded(Ok(jotforms)));
+        let mut headless = Self {
+            app,
+            backend,
+            commands: Vec::new(),
+            pending: VecDeque::new(),
+        };
+        headless.collect();
+        headless
+    }
+
+    /// Presses each key in `keys` in turn. Characters stand for themselves;
+    /// `<Up>`, `<Down>`, `<Left>`, `<Right>`, `<Enter>`, `<Esc>`, `<Tab>`,
+    /// `<BS>`, `<PgUp>`, `<PgDn>` and `<C-x>` (Ctrl and a letter) name the
+    /// rest.
+    pub fn keys(&mut self, keys: &str) -> &mut Self {
+        let mut rest = keys;
+        while let Some(c) = rest.chars().next() {
+            let name = rest
+                .strip_prefix('<')
+                .and_then(|tail| tail.split_once('>'))
+                .map(|(name, _)| name);
+            let key = match name.and_then(named_key) {
+                Some(key) => {
+                    rest = &rest[name.unwrap_or_default().len() + 2..];
+                    key
+                }
+                None => {
+                    rest = &rest[c.len_utf8()..];
+                    KeyEvent::from(KeyCode::Char(c))
+                }
+            };
+            self.app.handle_key_events(key);
+            self.collect();
+        }
+        self
+    }
+
+    /// Runs the commands asked for so far against the backend and hands the
+    /// outcomes back to the app, until it asks for nothing more.
+    pub async fn settle(&mut self) -> &mut Self {
+        while let Some(command) = self.pending.pop_front() {
+            let message = command.run(self.backend.as_ref()).await;
+            self.app.handle_message(message);
+            self.collect();
+        }
+        self
+    }
+
+    /// Ids of the rows in the table, top to bottom.
+    pub fn order(&self) -> Vec<&str> {
+        self.app
+            .visible_jotforms()
+            .into_iter()
+            .map(|j| j.id.as_str())
+            .collect()
+    }
+
+    fn collect(&mut self) {
+        let commands = self.app.take_commands();
+        self.commands.extend(commands.iter().cloned());
+        self.pending.extend(commands);
+    }
+}
+
+fn named_key(name: &str) -> Option<KeyEvent> {
+    let code = match name {
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Tab" => KeyCode::Tab,
+        "BS" => KeyCode::Backspace,
+        "PgUp" => KeyCode::PageUp,
+        "PgDn" => KeyCode::PageDown,
+        _ => {
+            let letter = name.strip_prefix("C-")?;
+            let mut chars = letter.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return None;
+            };
+            return Some(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+        }
+    };
+    Some(KeyEvent::from(code))
+}
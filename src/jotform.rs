@@ -0,0 +1,610 @@
+use async_trait::async_trait;
+use reqwest::{StatusCode, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    api::{decode, send},
+    backend::TicketBackend,
+    error::{JotviewError, Result},
+    model::{FullName, Jotform, JotformPatch, NewJotform, Status, SubmissionDate},
+};
+
+pub const DEFAULT_API_URL: &str = "https://api.jotform.com";
+
+/// Submissions fetched per page; the API's own maximum.
+const PAGE_SIZE: usize = 1000;
+
+/// Settings for reading tickets straight from a form on jotform.com.
+#[derive(Debug, Clone)]
+pub struct JotformConfig {
+    /// API root, ending in `/`; point it at a mock server to test.
+    pub api_url: Url,
+    pub api_key: String,
+    pub form_id: String,
+    pub questions: QuestionIds,
+}
+
+/// Which of the form's questions holds each ticket field, by question ID
+/// (the number shown in the form builder's question properties).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct QuestionIds {
+    /// A Full Name question, or a text box split at the first space.
+    pub submitter_name: String,
+    pub location: String,
+    pub exhibit_name: String,
+    pub description: String,
+    pub priority_level: String,
+    pub department: String,
+    /// Where jotview keeps the status; usually a hidden dropdown.
+    pub status: String,
+    pub assignee: Option<String>,
+}
+
+/// Client for the jotform.com REST API.
+#[derive(Debug, Clone)]
+pub struct JotformClient {
+    http: reqwest::Client,
+    config: Arc<JotformConfig>,
+}
+
+/// Every API response wraps its payload like this. `content` is only the
+/// payload on success; failures put something else there, often `""`.
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    #[serde(rename = "responseCode")]
+    response_code: u16,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    content: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct Submission {
+    id: String,
+    /// `YYYY-MM-DD HH:MM:SS`.
+    created_at: String,
+    /// Null until the submission is first edited.
+    updated_at: Option<String>,
+    #[serde(default)]
+    status: String,
+    /// Keyed by question ID; an empty submission has `[]` here instead.
+    #[serde(default)]
+    answers: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct Created {
+    #[serde(rename = "submissionID")]
+    submission_id: String,
+}
+
+impl JotformClient {
+    pub fn new(config: JotformConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            config: Arc::new(config),
+        }
+    }
+
+    pub fn submissions_url(&self) -> Url {
+        self.endpoint(&format!("form/{}/submissions", self.config.form_id))
+    }
+
+    fn endpoint(&self, path: &str) -> Url {
+        self.config
+            .api_url
+            .join(path)
+            .expect("endpoint paths are valid relative URLs")
+    }
+
+    /// Edits a submission's answers and returns it as stored.
+    ///
+    /// The API has no conditional edit. When `version` is given, the
+    /// submission's `updated_at` is checked against it first; that leaves a
+    /// short window for a concurrent edit to slip through, but catches the
+    /// usual case of a copy that went stale while on screen.
+    async fn edit(
+        &self,
+        id: &str,
+        answers: Vec<(String, String)>,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>> {
+        let url = self.endpoint(&format!("submission/{}", id));
+        let current = self.get(id).await?;
+        if version.is_some_and(|version| current.updated_at.as_deref() != Some(version)) {
+            return Err(JotviewError::Conflict {
+                url: url.to_string(),
+                current: Some(Box::new(current)),
+            });
+        }
+        let _: Value = self.post(&url, &answers).await?;
+        self.get(id).await.map(Some)
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, url: &Url) -> Result<T> {
+        let request = self
+            .http
+            .get(url.clone())
+            .header("APIKEY", &self.config.api_key);
+        unwrap(&send(request, url).await?, url)
+    }
+
+    async fn post<T: DeserializeOwned>(&self, url: &Url, form: &[(String, String)]) -> Result<T> {
+        let request = self
+            .http
+            .post(url.clone())
+            .header("APIKEY", &self.config.api_key)
+            .form(form);
+        unwrap(&send(request, url).await?, url)
+    }
+
+    fn to_jotform(&self, submission: Submission) -> Jotform {
+        let q = &self.config.questions;
+        let answers = match submission.answers {
+            Value::Object(answers) => answers.into_iter().collect(),
+            _ => HashMap::new(),
+        };
+        let text = |question: &str| answer_text(answers.get(question));
+        let (date, time) = submission
+            .created_at
+            .split_once(' ')
+            .unwrap_or((&submission.created_at, ""));
+        let status = text(&q.status);
+        Jotform {
+            id: submission.id,
+            submitter_name: full_name(answers.get(&q.submitter_name)),
+            created_at: SubmissionDate {
+                date: date.to_string(),
+                time: time.to_string(),
+            },
+            location: text(&q.location),
+            exhibit_name: text(&q.exhibit_name),
+            description: text(&q.description),
+            priority_level: text(&q.priority_level).into(),
+            department: text(&q.department).into(),
+            // Nothing sets the status on the public form.
+            status: if status.is_empty() {
+                Status::Open
+            } else {
+                status.into()
+            },
+            assignee: q
+                .assignee
+                .as_deref()
+                .map(text)
+                .filter(|assignee| !assignee.is_empty()),
+            updated_at: submission.updated_at,
+        }
+    }
+}
+
+#[async_trait]
+impl TicketBackend for JotformClient {
+    fn location(&self) -> String {
+        self.submissions_url().to_string()
+    }
+
+    /// Every submission that hasn't been deleted, across as many pages as it
+    /// takes.
+    async fn list(&self) -> Result<Vec<Jotform>> {
+        let mut jotforms = Vec::new();
+        for offset in (0..).step_by(PAGE_SIZE) {
+            let mut url = self.submissions_url();
+            url.query_pairs_mut()
+                .append_pair("limit", &PAGE_SIZE.to_string())
+                .append_pair("offset", &offset.to_string());
+            let page: Vec<Submission> = self.fetch(&url).await?;
+            let count = page.len();
+            jotforms.extend(
+                page.into_iter()
+                    .filter(|submission| submission.status != "DELETED")
+                    .map(|submission| self.to_jotform(submission)),
+            );
+            if count < PAGE_SIZE {
+                break;
+            }
+        }
+        Ok(jotforms)
+    }
+
+    async fn get(&self, id: &str) -> Result<Jotform> {
+        let url = self.endpoint(&format!("submission/{}", id));
+        let submission = self.fetch(&url).await?;
+        Ok(self.to_jotform(submission))
+    }
+
+    async fn create(&self, jotform: &NewJotform) -> Result<Jotform> {
+        let q = &self.config.questions;
+        let mut answers = name_answers(&q.submitter_name, &jotform.submitter_name);
+        answers.extend([
+            answer(&q.location, &jotform.location),
+            answer(&q.exhibit_name, &jotform.exhibit_name),
+            answer(&q.description, &jotform.description),
+            answer(&q.priority_level, jotform.priority_level.as_str()),
+            answer(&q.department, jotform.department.as_str()),
+            answer(&q.status, jotform.status.as_str()),
+        ]);
+        let url = self.submissions_url();
+        let created: Created = self.post(&url, &answers).await?;
+        self.get(&created.submission_id).await
+    }
+
+    /// Saved as the answer to the status question.
+    async fn update_status(
+        &self,
+        id: &str,
+        status: &Status,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>> {
+        let answers = vec![answer(&self.config.questions.status, status.as_str())];
+        self.edit(id, answers, version).await
+    }
+
+    async fn update_fields(
+        &self,
+        id: &str,
+        patch: &JotformPatch,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>> {
+        let q = &self.config.questions;
+        let mut answers = Vec::new();
+        if let Some(location) = &patch.location {
+            answers.push(answer(&q.location, location));
+        }
+        if let Some(exhibit_name) = &patch.exhibit_name {
+            answers.push(answer(&q.exhibit_name, exhibit_name));
+        }
+        if let Some(priority) = &patch.priority_level {
+            answers.push(answer(&q.priority_level, priority.as_str()));
+        }
+        if let Some(department) = &patch.department {
+            answers.push(answer(&q.department, department.as_str()));
+        }
+        if let Some(assignee) = &patch.assignee {
+            let Some(question) = &q.assignee else {
+                return Err(JotviewError::Unsupported {
+                    what: "assigning tickets without jotform.questions.assignee",
+                });
+            };
+            answers.push(answer(question, assignee.as_deref().unwrap_or_default()));
+        }
+        self.edit(id, answers, version).await
+    }
+}
+
+/// The payload of a successful response; a failure reported in the body
+/// becomes a [`JotviewError::HttpStatus`] like one reported in the header.
+fn unwrap<T: DeserializeOwned>(body: &str, url: &Url) -> Result<T> {
+    let envelope: Envelope = decode(body, url)?;
+    if envelope.response_code != 200 {
+        return Err(JotviewError::HttpStatus {
+            url: url.to_string(),
+            status: StatusCode::from_u16(envelope.response_code).unwrap_or(StatusCode::BAD_GATEWAY),
+            body: envelope.message,
+        });
+    }
+    serde_json::from_value(envelope.content).map_err(|source| JotviewError::Decode {
+        url: url.to_string(),
+        source,
+    })
+}
+
+/// A form field setting one question's answer.
+fn answer(question: &str, value: &str) -> (String, String) {
+    (format!("submission[{}]", question), value.to_string())
+}
+
+fn name_answers(question: &str, name: &FullName) -> Vec<(String, String)> {
+    vec![
+        (
+            format!("submission[{}][first]", question),
+            name.first.clone(),
+        ),
+        (format!("submission[{}][last]", question), name.last.clone()),
+    ]
+}
+
+/// An answer as plain text, however the question type shapes it.
+fn answer_text(answer: Option<&Value>) -> String {
+    let Some(value) = answer.and_then(|answer| answer.get("answer")) else {
+        return String::new();
+    };
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.trim().to_string(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map_or_else(|| item.to_string(), str::to_string)
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        Value::Object(parts) => parts
+            .values()
+            .filter_map(Value::as_str)
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+        other => other.to_string(),
+    }
+}
+
+fn full_name(answer: Option<&Value>) -> FullName {
+    let part = |key: &str| {
+        answer
+            .and_then(|answer| answer.get("answer"))
+            .and_then(|value| value.get(key))
+            .and_then(Value::as_str)
+            .map(|part| part.trim().to_string())
+    };
+    if let (Some(first), Some(last)) = (part("first"), part("last")) {
+        return FullName { first, last };
+    }
+    let text = answer_text(answer);
+    let (first, last) = text.split_once(' ').unwrap_or((&text, ""));
+    FullName {
+        first: first.to_string(),
+        last: last.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Department, Priority};
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Method, Request, Response,
+    };
+    use serde_json::json;
+    use std::{
+        convert::Infallible,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+
+    fn client() -> JotformClient {
+        client_for(Url::parse("http://localhost:9/").unwrap())
+    }
+
+    fn client_for(api_url: Url) -> JotformClient {
+        JotformClient::new(JotformConfig {
+            api_url,
+            api_key: "key".to_string(),
+            form_id: "42".to_string(),
+            questions: QuestionIds {
+                submitter_name: "3".to_string(),
+                location: "4".to_string(),
+                exhibit_name: "5".to_string(),
+                description: "6".to_string(),
+                priority_level: "7".to_string(),
+                department: "8".to_string(),
+                status: "9".to_string(),
+                assignee: Some("10".to_string()),
+            },
+        })
+    }
+
+    #[test]
+    fn answers_map_to_fields_by_question_id() {
+        let body = r#"{
+            "responseCode": 200,
+            "message": "success",
+            "content": [{
+                "id": "5801",
+                "created_at": "2024-05-01 09:30:00",
+                "updated_at": null,
+                "status": "ACTIVE",
+                "answers": {
+                    "3": {"type": "control_fullname", "answer": {"first": "Ann", "last": "Lee"}},
+                    "4": {"type": "control_textbox", "answer": "Lobby"},
+                    "5": {"type": "control_textbox", "answer": "Dinosaurs"},
+                    "6": {"type": "control_textarea", "answer": "Lamp out"},
+                    "7": {"type": "control_dropdown", "answer": "High"},
+                    "8": {"type": "control_dropdown", "answer": "Exhibits"},
+                    "10": {"type": "control_textbox", "answer": ""}
+                }
+            }, {
+                "id": "5802",
+                "created_at": "2024-05-02 10:00:00",
+                "updated_at": "2024-05-03 08:00:00",
+                "status": "ACTIVE",
+                "answers": []
+            }]
+        }"#;
+        let url = Url::parse("http://localhost:9/form/42/submissions").unwrap();
+        let submissions: Vec<Submission> = unwrap(body, &url).unwrap();
+        let client = client();
+        let jotforms: Vec<Jotform> = submissions
+            .into_iter()
+            .map(|submission| client.to_jotform(submission))
+            .collect();
+
+        let ann = &jotforms[0];
+        assert_eq!(ann.submitter_name.last, "Lee");
+        assert_eq!(ann.created_at.time, "09:30:00");
+        assert_eq!(ann.exhibit_name, "Dinosaurs");
+        assert_eq!(ann.priority_level, Priority::High);
+        assert_eq!(ann.department, Department::Exhibits);
+        assert_eq!(ann.status, Status::Open);
+        assert_eq!(ann.assignee, None);
+        assert_eq!(
+            jotforms[1].updated_at.as_deref(),
+            Some("2024-05-03 08:00:00")
+        );
+
+        let error = r#"{"responseCode": 401, "message": "You're not authorized", "content": ""}"#;
+        let err = unwrap::<Vec<Submission>>(error, &url).unwrap_err();
+        assert!(err.to_string().contains("401"), "{err}");
+    }
+
+    /// The requests a [`serve`] mock has seen: method, path and query, and
+    /// the `APIKEY` header.
+    type Log = Arc<Mutex<Vec<(Method, String, Option<String>)>>>;
+
+    /// Serves `submissions` the way jotform.com does: paged under
+    /// `/form/42/submissions`, and one at a time, editable, under
+    /// `/submission/{id}`. Each edit bumps `updated_at`.
+    fn serve(submissions: Vec<Value>) -> (Url, Log) {
+        let submissions = Arc::new(Mutex::new(submissions));
+        let log = Log::default();
+        let make_service = {
+            let log = log.clone();
+            make_service_fn(move |_| {
+                let (submissions, log) = (submissions.clone(), log.clone());
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request| {
+                        respond(submissions.clone(), log.clone(), request)
+                    }))
+                }
+            })
+        };
+        let server =
+            hyper::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+        let url = Url::parse(&format!("http://{}/", server.local_addr())).unwrap();
+        tokio::spawn(server);
+        (url, log)
+    }
+
+    async fn respond(
+        submissions: Arc<Mutex<Vec<Value>>>,
+        log: Log,
+        request: Request<Body>,
+    ) -> std::result::Result<Response<Body>, Infallible> {
+        let method = request.method().clone();
+        let uri = request.uri().clone();
+        let api_key = request
+            .headers()
+            .get("APIKEY")
+            .and_then(|key| key.to_str().ok())
+            .map(str::to_string);
+        log.lock()
+            .unwrap()
+            .push((method.clone(), uri.to_string(), api_key));
+        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+        let form = Url::parse(&format!("http://mock/?{}", String::from_utf8_lossy(&body))).unwrap();
+
+        let mut submissions = submissions.lock().unwrap();
+        let query = Url::parse(&format!("http://mock{}", uri)).unwrap();
+        let param = |name: &str| {
+            query
+                .query_pairs()
+                .find(|(key, _)| key == name)
+                .and_then(|(_, value)| value.parse::<usize>().ok())
+        };
+        let segments: Vec<&str> = uri.path().split('/').collect();
+        let content = match (&method, &segments[..]) {
+            (&Method::GET, ["", "form", "42", "submissions"]) => {
+                let (offset, limit) = (param("offset").unwrap_or(0), param("limit").unwrap_or(20));
+                Some(json!(submissions
+                    .iter()
+                    .skip(offset)
+                    .take(limit)
+                    .collect::<Vec<_>>()))
+            }
+            (_, ["", "submission", id]) => submissions
+                .iter_mut()
+                .find(|submission| submission["id"] == *id)
+                .map(|submission| {
+                    if method == Method::POST {
+                        for (key, value) in form.query_pairs() {
+                            let question =
+                                key.trim_start_matches("submission[").trim_end_matches(']');
+                            submission["answers"][question] = json!({ "answer": value });
+                        }
+                        let revision = submission["updated_at"].as_str().unwrap_or("0");
+                        let next = revision.parse::<u32>().unwrap() + 1;
+                        submission["updated_at"] = json!(next.to_string());
+                    }
+                    submission.clone()
+                }),
+            _ => None,
+        };
+        let envelope = match content {
+            Some(content) => json!({"responseCode": 200, "message": "success", "content": content}),
+            None => json!({"responseCode": 404, "message": "Not found", "content": ""}),
+        };
+        Ok(Response::new(Body::from(envelope.to_string())))
+    }
+
+    fn submission(id: usize, status: &str) -> Value {
+        json!({
+            "id": id.to_string(),
+            "created_at": "2024-05-01 09:30:00",
+            "updated_at": "1",
+            "status": status,
+            "answers": {
+                "3": {"answer": {"first": "Ann", "last": "Lee"}},
+                "9": {"answer": "Open"}
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn lists_pages_and_edits_against_the_api() {
+        // One more than a page, so a second page is needed; one is deleted.
+        let mut submissions: Vec<Value> = (1..=PAGE_SIZE + 1)
+            .map(|id| submission(id, "ACTIVE"))
+            .collect();
+        submissions[1]["status"] = json!("DELETED");
+        let (url, log) = serve(submissions);
+        let client = client_for(url);
+
+        let jotforms = client.list().await.unwrap();
+        assert_eq!(jotforms.len(), PAGE_SIZE);
+        assert_eq!(jotforms.last().unwrap().id, (PAGE_SIZE + 1).to_string());
+        assert!(jotforms.iter().all(|jotform| jotform.id != "2"));
+        let requests: Vec<String> = log
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, path, _)| path.clone())
+            .collect();
+        assert_eq!(
+            requests,
+            [
+                "/form/42/submissions?limit=1000&offset=0",
+                "/form/42/submissions?limit=1000&offset=1000"
+            ]
+        );
+
+        let closed = client
+            .update_status("1", &Status::Closed, Some("1"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(closed.status, Status::Closed);
+        assert_eq!(closed.updated_at.as_deref(), Some("2"));
+        assert!(log
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(method, path, _)| method == Method::POST && path == "/submission/1"));
+
+        // The copy on screen still says revision 1.
+        let err = client
+            .update_status("1", &Status::InProgress, Some("1"))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, JotviewError::Conflict { current: Some(ref current), .. } if current.status == Status::Closed)
+        );
+        // Without a revision to compare, the change goes through.
+        let reopened = client
+            .update_status("1", &Status::Open, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reopened.updated_at.as_deref(), Some("3"));
+
+        assert!(log
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|(_, _, api_key)| api_key.as_deref() == Some("key")));
+    }
+}
@@ -0,0 +1,4 @@
+//! Ticket types shared by the `jotview` TUI and `jotview-mock-server`.
+
+pub mod error;
+pub mod model;
@@ -0,0 +1,372 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::error::JotviewError;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Jotform {
+    pub id: String,
+    pub submitter_name: FullName,
+    pub created_at: SubmissionDate,
+    pub location: String,
+    pub exhibit_name: String,
+    pub description: String,
+    pub priority_level: Priority,
+    pub department: Department,
+    pub status: Status,
+    /// Staff member working on the ticket; older backends omit it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// Revision stamp the backend changes on every update, sent back in
+    /// `If-Match` so a save can't silently overwrite someone else's. Opaque
+    /// to jotview; older backends omit it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+/// A ticket logged from jotview, POSTed to the list endpoint. The backend
+/// assigns the id and returns the stored [`Jotform`].
+#[derive(Debug, Clone, Serialize)]
+pub struct NewJotform {
+    pub submitter_name: FullName,
+    pub created_at: SubmissionDate,
+    pub location: String,
+    pub exhibit_name: String,
+    pub description: String,
+    pub priority_level: Priority,
+    pub department: Department,
+    pub status: Status,
+}
+
+/// One entry in a ticket's work log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Note {
+    pub created_at: SubmissionDate,
+    pub text: String,
+}
+
+/// Changed fields of a jotform, sent as a PATCH body. Unset fields are
+/// omitted so the backend leaves them alone.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JotformPatch {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exhibit_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority_level: Option<Priority>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub department: Option<Department>,
+    /// `Some(None)` unassigns, and is sent as `null`.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "present"
+    )]
+    pub assignee: Option<Option<String>>,
+}
+
+impl JotformPatch {
+    pub fn is_empty(&self) -> bool {
+        *self == JotformPatch::default()
+    }
+
+    /// Adds `later`'s fields, which win where both set one.
+    pub fn merge(&mut self, later: JotformPatch) {
+        self.location = later.location.or(self.location.take());
+        self.exhibit_name = later.exhibit_name.or(self.exhibit_name.take());
+        self.priority_level = later.priority_level.or(self.priority_level.take());
+        self.department = later.department.or(self.department.take());
+        self.assignee = later.assignee.or(self.assignee.take());
+    }
+
+    pub fn apply_to(&self, jotform: &mut Jotform) {
+        if let Some(location) = &self.location {
+            jotform.location = location.clone();
+        }
+        if let Some(exhibit_name) = &self.exhibit_name {
+            jotform.exhibit_name = exhibit_name.clone();
+        }
+        if let Some(priority) = &self.priority_level {
+            jotform.priority_level = priority.clone();
+        }
+        if let Some(department) = &self.department {
+            jotform.department = department.clone();
+        }
+        if let Some(assignee) = &self.assignee {
+            jotform.assignee = assignee.clone();
+        }
+    }
+
+    /// The patch that puts back `base`'s values for the fields this one sets.
+    pub fn undo(&self, base: &Jotform) -> JotformPatch {
+        JotformPatch {
+            location: self.location.as_ref().map(|_| base.location.clone()),
+            exhibit_name: self
+                .exhibit_name
+                .as_ref()
+                .map(|_| base.exhibit_name.clone()),
+            priority_level: self
+                .priority_level
+                .as_ref()
+                .map(|_| base.priority_level.clone()),
+            department: self.department.as_ref().map(|_| base.department.clone()),
+            assignee: self.assignee.as_ref().map(|_| base.assignee.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FullName {
+    pub first: String,
+    pub last: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SubmissionDate {
+    pub date: String,
+    pub time: String,
+}
+
+impl SubmissionDate {
+    /// The submission date, which the backend sends as `YYYY-MM-DD`.
+    pub fn parsed_date(&self) -> Result<NaiveDate, JotviewError> {
+        NaiveDate::parse_from_str(&self.date, "%Y-%m-%d").map_err(|source| {
+            JotviewError::DateParse {
+                value: self.date.clone(),
+                source,
+            }
+        })
+    }
+
+    /// Date and time combined. A missing or unrecognised time counts as
+    /// midnight so the date alone still orders correctly.
+    pub fn timestamp(&self) -> Option<NaiveDateTime> {
+        let date = self.parsed_date().ok()?;
+        let time = ["%H:%M:%S", "%H:%M", "%I:%M %p"]
+            .iter()
+            .find_map(|format| NaiveTime::parse_from_str(self.time.trim(), format).ok())
+            .unwrap_or(NaiveTime::MIN);
+        Some(date.and_time(time))
+    }
+
+    /// How long ago the ticket was submitted, e.g. "3 days ago".
+    pub fn relative_age(&self, now: NaiveDateTime) -> Option<String> {
+        let elapsed = now.signed_duration_since(self.timestamp()?);
+        let plural =
+            |n: i64, unit: &str| format!("{} {}{} ago", n, unit, if n == 1 { "" } else { "s" });
+        Some(match elapsed {
+            e if e < TimeDelta::zero() => "in the future".to_string(),
+            e if e < TimeDelta::minutes(1) => "just now".to_string(),
+            e if e < TimeDelta::hours(1) => plural(e.num_minutes(), "minute"),
+            e if e < TimeDelta::days(1) => plural(e.num_hours(), "hour"),
+            e if e < TimeDelta::days(2) => "yesterday".to_string(),
+            e if e < TimeDelta::weeks(2) => plural(e.num_days(), "day"),
+            e if e < TimeDelta::days(60) => plural(e.num_weeks(), "week"),
+            e if e < TimeDelta::days(365) => plural(e.num_days() / 30, "month"),
+            e => plural(e.num_days() / 365, "year"),
+        })
+    }
+}
+
+/// Reads a field that is present, even as `null`, as `Some`; a missing field
+/// falls back to `None` through `#[serde(default)]`.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Declares an enum that (de)serializes as a plain string.
+///
+/// Each listed variant maps to its wire label; any other string is kept
+/// verbatim in `Unknown` so new backend values never fail deserialization.
+/// `KNOWN` lists the variants in declaration order and `rank` is the
+/// position in it, with unknown values after every known one.
+/// Declares a string-backed enum with an `Unknown(String)` fallback. Each
+/// variant gives its wire label and colour, and optionally its place in the
+/// table's sort order (declaration order otherwise); `unknown` does the same
+/// for values the backend sends that aren't listed.
+macro_rules! string_enum {
+    (@or $value:expr; $default:expr) => {
+        $value
+    };
+    (@or ; $default:expr) => {
+        $default
+    };
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident => $label:literal {
+                color: $color:expr $(, sort_rank: $rank:expr)? $(,)?
+            }),+ $(,)?
+        }
+        unknown { color: $unknown_color:expr $(, sort_rank: $unknown_rank:expr)? $(,)? }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($variant,)+
+            Unknown(String),
+        }
+
+        impl $name {
+            pub const KNOWN: &'static [$name] = &[$($name::$variant),+];
+
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $label,)+
+                    $name::Unknown(value) => value,
+                }
+            }
+
+            pub fn rank(&self) -> usize {
+                $name::KNOWN
+                    .iter()
+                    .position(|known| known == self)
+                    .unwrap_or($name::KNOWN.len())
+            }
+
+            /// Position when the table is sorted by this column.
+            pub fn sort_rank(&self) -> usize {
+                match self {
+                    $($name::$variant => string_enum!(@or $($rank)?; self.rank()),)+
+                    $name::Unknown(_) => string_enum!(@or $($unknown_rank)?; self.rank()),
+                }
+            }
+
+            pub fn color(&self) -> Color {
+                match self {
+                    $($name::$variant => $color,)+
+                    $name::Unknown(_) => $unknown_color,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match value.as_str() {
+                    $($label => $name::$variant,)+
+                    _ => $name::Unknown(value),
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                match value {
+                    $name::Unknown(value) => value,
+                    known => known.as_str().to_string(),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// Workflow state of a ticket. `KNOWN` order is the order of the status
+    /// menu; the table groups in-progress work first, unplanned tickets last.
+    pub enum Status {
+        Open => "Open" { color: Color::Rgb(144, 238, 144), sort_rank: 1 },
+        InProgress => "InProgress" { color: Color::Rgb(216, 191, 216), sort_rank: 0 },
+        Closed => "Closed" { color: Color::Rgb(255, 182, 193), sort_rank: 1 },
+        Unplanned => "Unplanned" { color: Color::Rgb(105, 105, 105), sort_rank: 2 },
+    }
+    unknown { color: Color::DarkGray, sort_rank: 1 }
+}
+
+string_enum! {
+    pub enum Priority {
+        Low => "Low" { color: Color::Rgb(144, 238, 144) },
+        Medium => "Medium" { color: Color::Rgb(255, 255, 153) },
+        High => "High" { color: Color::Rgb(255, 182, 193) },
+    }
+    unknown { color: Color::DarkGray }
+}
+
+string_enum! {
+    pub enum Department {
+        Exhibits => "Exhibits" { color: Color::Rgb(255, 183, 82) },
+        Operations => "Operations" { color: Color::Rgb(173, 216, 230) },
+    }
+    unknown { color: Color::DarkGray }
+}
+
+impl Status {
+    /// Whether the ticket still needs work.
+    pub fn is_open(&self) -> bool {
+        matches!(self, Status::Open | Status::InProgress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_rank_falls_back_to_declaration_order() {
+        let mut statuses = Status::KNOWN.to_vec();
+        statuses.push(Status::Unknown("Blocked".to_string()));
+        statuses.sort_by_key(Status::sort_rank);
+        assert_eq!(statuses[0], Status::InProgress);
+        assert_eq!(statuses.last(), Some(&Status::Unplanned));
+
+        assert_eq!(Priority::High.sort_rank(), 2);
+        assert_eq!(Priority::Unknown("Urgent".to_string()).sort_rank(), 3);
+    }
+
+    #[test]
+    fn unknown_values_round_trip() {
+        let status: Status = serde_json::from_str("\"Blocked\"").unwrap();
+        assert_eq!(status, Status::Unknown("Blocked".to_string()));
+        assert_eq!(serde_json::to_string(&status).unwrap(), "\"Blocked\"");
+
+        let department: Department = serde_json::from_str("\"Exhibits\"").unwrap();
+        assert_eq!(department, Department::Exhibits);
+        assert_eq!(serde_json::to_string(&department).unwrap(), "\"Exhibits\"");
+    }
+
+    #[test]
+    fn relative_age_picks_a_readable_unit() {
+        let submitted = SubmissionDate {
+            date: "2024-05-01".to_string(),
+            time: "09:30".to_string(),
+        };
+        let at = |date: &str, time: &str| {
+            SubmissionDate {
+                date: date.to_string(),
+                time: time.to_string(),
+            }
+            .timestamp()
+            .unwrap()
+        };
+
+        let age = |now| submitted.relative_age(now).unwrap();
+        assert_eq!(age(at("2024-05-01", "09:30:30")), "just now");
+        assert_eq!(age(at("2024-05-01", "10:29")), "59 minutes ago");
+        assert_eq!(age(at("2024-05-01", "11:30")), "2 hours ago");
+        assert_eq!(age(at("2024-05-02", "12:00")), "yesterday");
+        assert_eq!(age(at("2024-05-04", "09:30")), "3 days ago");
+        assert_eq!(age(at("2024-05-22", "09:30")), "3 weeks ago");
+        assert_eq!(age(at("2024-08-01", "09:30")), "3 months ago");
+        assert_eq!(age(at("2026-05-01", "09:30")), "2 years ago");
+        assert_eq!(age(at("2024-04-30", "09:30")), "in the future");
+
+        let undated = SubmissionDate {
+            date: "soon".to_string(),
+            time: String::new(),
+        };
+        assert_eq!(undated.relative_age(at("2024-05-01", "09:30")), None);
+    }
+}
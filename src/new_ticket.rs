@@ -0,0 +1,210 @@
+use chrono::NaiveDateTime;
+
+use crate::{
+    edit_form::cycle,
+    model::{Department, FullName, NewJotform, Priority, Status, SubmissionDate},
+};
+
+/// Longest single-line value the form accepts.
+const MAX_TEXT_LEN: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketField {
+    FirstName,
+    LastName,
+    Location,
+    Exhibit,
+    Priority,
+    Department,
+    Description,
+}
+
+impl TicketField {
+    pub const ALL: [TicketField; 7] = [
+        TicketField::FirstName,
+        TicketField::LastName,
+        TicketField::Location,
+        TicketField::Exhibit,
+        TicketField::Priority,
+        TicketField::Department,
+        TicketField::Description,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TicketField::FirstName => "First name",
+            TicketField::LastName => "Last name",
+            TicketField::Location => "Location",
+            TicketField::Exhibit => "Exhibit",
+            TicketField::Priority => "Priority",
+            TicketField::Department => "Department",
+            TicketField::Description => "Description",
+        }
+    }
+
+    pub fn is_choice(self) -> bool {
+        matches!(self, TicketField::Priority | TicketField::Department)
+    }
+}
+
+/// Overlay for logging a new ticket, opened with `n`.
+#[derive(Debug, Clone)]
+pub struct NewTicketForm {
+    pub first_name: String,
+    pub last_name: String,
+    pub location: String,
+    pub exhibit_name: String,
+    pub priority: Priority,
+    pub department: Department,
+    /// May span several lines; Enter inserts a newline here.
+    pub description: String,
+    /// Index into `TicketField::ALL`.
+    pub focus: usize,
+    pub error: Option<String>,
+    /// Set while the POST is in flight; the form stays open so nothing is
+    /// lost if it fails.
+    pub saving: bool,
+}
+
+impl Default for NewTicketForm {
+    fn default() -> Self {
+        Self {
+            first_name: String::new(),
+            last_name: String::new(),
+            location: String::new(),
+            exhibit_name: String::new(),
+            priority: Priority::Medium,
+            department: Department::Exhibits,
+            description: String::new(),
+            focus: 0,
+            error: None,
+            saving: false,
+        }
+    }
+}
+
+impl NewTicketForm {
+    pub fn focused(&self) -> TicketField {
+        TicketField::ALL[self.focus]
+    }
+
+    pub fn move_focus(&mut self, delta: isize) {
+        let len = TicketField::ALL.len() as isize;
+        self.focus = (self.focus as isize + delta).rem_euclid(len) as usize;
+    }
+
+    pub fn cycle_choice(&mut self, delta: isize) {
+        match self.focused() {
+            TicketField::Priority => self.priority = cycle(Priority::KNOWN, &self.priority, delta),
+            TicketField::Department => {
+                self.department = cycle(Department::KNOWN, &self.department, delta);
+            }
+            _ => {}
+        }
+    }
+
+    /// The focused text field, if it is one.
+    pub fn focused_text(&mut self) -> Option<&mut String> {
+        match self.focused() {
+            TicketField::FirstName => Some(&mut self.first_name),
+            TicketField::LastName => Some(&mut self.last_name),
+            TicketField::Location => Some(&mut self.location),
+            TicketField::Exhibit => Some(&mut self.exhibit_name),
+            TicketField::Description => Some(&mut self.description),
+            TicketField::Priority | TicketField::Department => None,
+        }
+    }
+
+    pub fn value(&self, field: TicketField) -> &str {
+        match field {
+            TicketField::FirstName => &self.first_name,
+            TicketField::LastName => &self.last_name,
+            TicketField::Location => &self.location,
+            TicketField::Exhibit => &self.exhibit_name,
+            TicketField::Priority => self.priority.as_str(),
+            TicketField::Department => self.department.as_str(),
+            TicketField::Description => &self.description,
+        }
+    }
+
+    /// The submission to POST, stamped with `now`, or the first problem
+    /// with the form.
+    pub fn submission(&self, now: NaiveDateTime) -> Result<NewJotform, String> {
+        for field in TicketField::ALL {
+            if field.is_choice() {
+                continue;
+            }
+            let value = self.value(field).trim();
+            if value.is_empty() {
+                return Err(format!("{} can't be empty", field.label()));
+            }
+            if field != TicketField::Description && value.chars().count() > MAX_TEXT_LEN {
+                return Err(format!(
+                    "{} must be at most {} characters",
+                    field.label(),
+                    MAX_TEXT_LEN
+                ));
+            }
+        }
+
+        Ok(NewJotform {
+            submitter_name: FullName {
+                first: self.first_name.trim().to_string(),
+                last: self.last_name.trim().to_string(),
+            },
+            created_at: SubmissionDate {
+                date: now.format("%Y-%m-%d").to_string(),
+                time: now.format("%H:%M").to_string(),
+            },
+            location: self.location.trim().to_string(),
+            exhibit_name: self.exhibit_name.trim().to_string(),
+            description: self.description.trim_end().to_string(),
+            priority_level: self.priority.clone(),
+            department: self.department.clone(),
+            status: Status::Open,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn submission_is_trimmed_and_stamped() {
+        let form = NewTicketForm {
+            first_name: " Ann".to_string(),
+            last_name: "Lee".to_string(),
+            location: "Lobby".to_string(),
+            exhibit_name: "Dinosaurs ".to_string(),
+            description: "Lamp is out\nsecond floor\n".to_string(),
+            ..NewTicketForm::default()
+        };
+        let now = NaiveDate::from_ymd_opt(2024, 5, 1)
+            .unwrap()
+            .and_hms_opt(9, 5, 0)
+            .unwrap();
+
+        let submission = form.submission(now).unwrap();
+        assert_eq!(submission.submitter_name.first, "Ann");
+        assert_eq!(submission.exhibit_name, "Dinosaurs");
+        assert_eq!(submission.description, "Lamp is out\nsecond floor");
+        assert_eq!(submission.created_at.date, "2024-05-01");
+        assert_eq!(submission.created_at.time, "09:05");
+        assert_eq!(submission.status, Status::Open);
+    }
+
+    #[test]
+    fn every_text_field_is_required() {
+        let form = NewTicketForm {
+            first_name: "Ann".to_string(),
+            ..NewTicketForm::default()
+        };
+        let now = NaiveDateTime::default();
+        assert_eq!(
+            form.submission(now).unwrap_err(),
+            "Last name can't be empty"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::NaiveDateTime;
+
+use crate::model::{Note, SubmissionDate};
+
+/// A ticket's work log as last fetched.
+#[derive(Debug, Clone, Default)]
+pub struct NoteThread {
+    pub notes: Vec<Note>,
+    pub loading: bool,
+    pub error: Option<String>,
+}
+
+/// Which pane of the detail view the scroll keys move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DetailPane {
+    #[default]
+    Description,
+    Notes,
+}
+
+impl DetailPane {
+    pub fn toggle(self) -> DetailPane {
+        match self {
+            DetailPane::Description => DetailPane::Notes,
+            DetailPane::Notes => DetailPane::Description,
+        }
+    }
+}
+
+/// Multi-line input for a new note, opened with `w` in the detail view.
+#[derive(Debug, Clone, Default)]
+pub struct NoteInput {
+    pub id: String,
+    pub text: String,
+    pub error: Option<String>,
+    /// Set while the POST is in flight.
+    pub saving: bool,
+}
+
+impl NoteInput {
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            ..NoteInput::default()
+        }
+    }
+
+    /// The note to POST, stamped with `now`.
+    pub fn note(&self, now: NaiveDateTime) -> Result<Note, String> {
+        let text = self.text.trim();
+        if text.is_empty() {
+            return Err("The note is empty".to_string());
+        }
+        Ok(Note {
+            created_at: SubmissionDate {
+                date: now.format("%Y-%m-%d").to_string(),
+                time: now.format("%H:%M").to_string(),
+            },
+            text: text.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn note_is_trimmed_and_must_not_be_blank() {
+        let now = NaiveDate::from_ymd_opt(2024, 5, 1)
+            .unwrap()
+            .and_hms_opt(14, 30, 0)
+            .unwrap();
+        let mut input = NoteInput::new("7");
+        input.text = "  \n ".to_string();
+        assert!(input.note(now).is_err());
+
+        input.text = "replaced bulb\nwaiting on part\n".to_string();
+        let note = input.note(now).unwrap();
+        assert_eq!(note.text, "replaced bulb\nwaiting on part");
+        assert_eq!(note.created_at.time, "14:30");
+    }
+}
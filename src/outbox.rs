@@ -0,0 +1,171 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backend::TicketBackend,
+    error::Result,
+    model::{Jotform, JotformPatch, Status},
+};
+
+/// A change made while offline, waiting to be sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    Status { status: Status },
+    Fields { patch: JotformPatch },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedChange {
+    pub id: String,
+    pub change: Change,
+    /// The row as it was before the first queued change of this kind, used
+    /// to tell whether the server copy has changed since.
+    pub base: Jotform,
+    pub queued_at: DateTime<Local>,
+}
+
+/// Changes made offline, oldest first. Persisted next to the cache so they
+/// survive a restart before the backend is reachable again.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Outbox {
+    pub changes: Vec<QueuedChange>,
+}
+
+impl Change {
+    pub fn apply_to(&self, jotform: &mut Jotform) {
+        match self {
+            Change::Status { status } => jotform.status = status.clone(),
+            Change::Fields { patch } => patch.apply_to(jotform),
+        }
+    }
+
+    /// Sends the change to ticket `id`, guarded by `version`; returns the
+    /// ticket as stored if the backend answers with it.
+    pub async fn send(
+        &self,
+        backend: &dyn TicketBackend,
+        id: &str,
+        version: Option<&str>,
+    ) -> Result<Option<Jotform>> {
+        match self {
+            Change::Status { status } => backend.update_status(id, status, version).await,
+            Change::Fields { patch } => backend.update_fields(id, patch, version).await,
+        }
+    }
+}
+
+impl Outbox {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Queues a change to the row that currently looks like `base`. A later
+    /// change of the same kind to the same row is folded into the earlier
+    /// one, which keeps its base.
+    pub fn queue(&mut self, base: &Jotform, change: Change) {
+        let existing = self.changes.iter_mut().find(|queued| {
+            queued.id == base.id
+                && std::mem::discriminant(&queued.change) == std::mem::discriminant(&change)
+        });
+        match (existing, change) {
+            (Some(queued), Change::Status { status }) => {
+                queued.change = Change::Status { status };
+            }
+            (Some(queued), Change::Fields { patch }) => {
+                if let Change::Fields {
+                    patch: queued_patch,
+                } = &mut queued.change
+                {
+                    queued_patch.merge(patch);
+                }
+            }
+            (None, change) => self.changes.push(QueuedChange {
+                id: base.id.clone(),
+                change,
+                base: base.clone(),
+                queued_at: Local::now(),
+            }),
+        }
+    }
+
+    /// Shows every queued change on the matching rows.
+    pub fn apply_to(&self, jotforms: &mut [Jotform]) {
+        for queued in &self.changes {
+            if let Some(jotform) = jotforms.iter_mut().find(|j| j.id == queued.id) {
+                queued.change.apply_to(jotform);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, done: &QueuedChange) {
+        self.changes
+            .retain(|queued| !(queued.id == done.id && queued.change == done.change));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::jotform;
+
+    #[test]
+    fn later_changes_fold_into_earlier_ones_and_survive_a_round_trip() {
+        let mut outbox = Outbox::default();
+        let base = jotform("7");
+        outbox.queue(
+            &base,
+            Change::Status {
+                status: Status::InProgress,
+            },
+        );
+        outbox.queue(
+            &base,
+            Change::Status {
+                status: Status::Closed,
+            },
+        );
+        outbox.queue(
+            &base,
+            Change::Fields {
+                patch: JotformPatch {
+                    location: Some("Atrium".to_string()),
+                    ..JotformPatch::default()
+                },
+            },
+        );
+        outbox.queue(
+            &base,
+            Change::Fields {
+                patch: JotformPatch {
+                    assignee: Some(None),
+                    ..JotformPatch::default()
+                },
+            },
+        );
+        assert_eq!(outbox.len(), 2);
+
+        let json = serde_json::to_string(&outbox).unwrap();
+        let outbox: Outbox = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            outbox.changes[0].change,
+            Change::Status {
+                status: Status::Closed
+            }
+        );
+        assert_eq!(
+            outbox.changes[1].change,
+            Change::Fields {
+                patch: JotformPatch {
+                    location: Some("Atrium".to_string()),
+                    assignee: Some(None),
+                    ..JotformPatch::default()
+                }
+            }
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Printable work orders, one page per ticket, as HTML and plain text. The
+//! layout comes from templates that can be replaced without rebuilding.
+
+use chrono::NaiveDateTime;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::ReportConfig,
+    error::{JotviewError, Result},
+    model::{Jotform, Priority},
+};
+
+const HTML_TEMPLATE: &str = include_str!("../templates/work_order.html");
+const TEXT_TEMPLATE: &str = include_str!("../templates/work_order.txt");
+
+/// Placeholders usable anywhere in a template.
+const REPORT_FIELDS: &[&str] = &["title", "generated", "count"];
+/// Placeholders usable between `{{#tickets}}` and `{{/tickets}}`.
+const TICKET_FIELDS: &[&str] = &[
+    "id",
+    "submitter",
+    "submitted",
+    "location",
+    "exhibit",
+    "priority",
+    "department",
+    "status",
+    "assignee",
+    "description",
+];
+
+/// A parsed template: text with `{{field}}` placeholders, and a
+/// `{{#tickets}}…{{/tickets}}` section repeated for every ticket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Field(String),
+    Tickets(Vec<Part>),
+}
+
+impl Template {
+    /// Parses `source`; `name` is only used in errors. Unknown placeholders
+    /// and unbalanced sections are errors rather than silently blank.
+    pub fn parse(name: &str, source: &str) -> Result<Self> {
+        let error = |reason: String| JotviewError::Format {
+            path: name.to_string(),
+            reason,
+        };
+        // The section being filled, innermost last.
+        let mut stack: Vec<Vec<Part>> = vec![Vec::new()];
+        let mut rest = source;
+        while let Some(start) = rest.find("{{") {
+            push_text(&mut stack, &rest[..start]);
+            let Some(end) = rest[start..].find("}}") else {
+                return Err(error("a \"{{\" is never closed".to_string()));
+            };
+            let tag = rest[start + 2..start + end].trim();
+            rest = &rest[start + end + 2..];
+            let (sigil, name) = match tag.chars().next() {
+                Some(c @ ('#' | '/')) => (Some(c), tag[1..].trim()),
+                _ => (None, tag),
+            };
+            match (sigil, name) {
+                (Some('#'), "tickets") if stack.len() == 1 => {
+                    stack.push(Vec::new());
+                    // A section tag on a line of its own doesn't leave a blank line.
+                    rest = rest.strip_prefix('\n').unwrap_or(rest);
+                }
+                (Some('#'), "tickets") => {
+                    return Err(error("{{#tickets}} sections can't be nested".to_string()))
+                }
+                (Some(_), "tickets") if stack.len() == 2 => {
+                    let section = stack.pop().expect("checked the depth");
+                    push(&mut stack, Part::Tickets(section));
+                    rest = rest.strip_prefix('\n').unwrap_or(rest);
+                }
+                (Some(_), "tickets") => {
+                    return Err(error("{{/tickets}} without {{#tickets}}".to_string()))
+                }
+                (Some(_), _) => {
+                    return Err(error(format!(
+                        "unknown section {{{{{}}}}}; the only one is tickets",
+                        tag
+                    )))
+                }
+                (None, _)
+                    if REPORT_FIELDS.contains(&name)
+                        || (stack.len() == 2 && TICKET_FIELDS.contains(&name)) =>
+                {
+                    push(&mut stack, Part::Field(name.to_string()));
+                }
+                (None, _) if TICKET_FIELDS.contains(&name) => {
+                    return Err(error(format!(
+                        "{{{{{}}}}} is only known inside {{{{#tickets}}}}",
+                        name
+                    )))
+                }
+                (None, _) => return Err(error(format!("unknown placeholder {{{{{}}}}}", name))),
+            }
+        }
+        push_text(&mut stack, rest);
+        if stack.len() != 1 {
+            return Err(error("{{#tickets}} is never closed".to_string()));
+        }
+        Ok(Self {
+            parts: stack.pop().expect("checked the depth"),
+        })
+    }
+
+    /// Fills the template in, passing every value through `escape`.
+    pub fn render(&self, report: &Report, escape: fn(&str) -> String) -> String {
+        let mut out = String::new();
+        render_parts(&self.parts, report, None, escape, &mut out);
+        out
+    }
+}
+
+fn push(stack: &mut [Vec<Part>], part: Part) {
+    stack.last_mut().expect("never empty").push(part);
+}
+
+fn push_text(stack: &mut [Vec<Part>], text: &str) {
+    if !text.is_empty() {
+        push(stack, Part::Text(text.to_string()));
+    }
+}
+
+fn render_parts(
+    parts: &[Part],
+    report: &Report,
+    ticket: Option<&Jotform>,
+    escape: fn(&str) -> String,
+    out: &mut String,
+) {
+    for part in parts {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            Part::Field(name) => out.push_str(&escape(&report.value(name, ticket))),
+            Part::Tickets(section) => {
+                for jotform in &report.jotforms {
+                    render_parts(section, report, Some(jotform), escape, out);
+                }
+            }
+        }
+    }
+}
+
+/// The tickets to print and what the page says about them.
+#[derive(Debug, Clone)]
+pub struct Report<'a> {
+    pub title: String,
+    pub jotforms: Vec<&'a Jotform>,
+    pub generated: NaiveDateTime,
+}
+
+impl<'a> Report<'a> {
+    /// A work order for one ticket.
+    pub fn single(jotform: &'a Jotform, generated: NaiveDateTime) -> Self {
+        Self {
+            title: format!("Work order #{}", jotform.id),
+            jotforms: vec![jotform],
+            generated,
+        }
+    }
+
+    /// Work orders for every High-priority ticket that's still to be done,
+    /// oldest first.
+    pub fn high_priority(jotforms: &'a [Jotform], generated: NaiveDateTime) -> Self {
+        let mut due: Vec<&Jotform> = jotforms
+            .iter()
+            .filter(|j| j.priority_level == Priority::High && j.status.is_open())
+            .collect();
+        due.sort_by_key(|j| j.created_at.timestamp());
+        Self {
+            title: "High-priority work orders".to_string(),
+            jotforms: due,
+            generated,
+        }
+    }
+
+    /// File names without extension: the ticket's id for one, a timestamp
+    /// for several.
+    pub fn file_stem(&self) -> String {
+        match self.jotforms.as_slice() {
+            [jotform] => format!("work-order-{}", jotform.id),
+            _ => format!("work-orders-{}", self.generated.format("%Y%m%d-%H%M%S")),
+        }
+    }
+
+    fn value(&self, name: &str, ticket: Option<&Jotform>) -> String {
+        match (name, ticket) {
+            ("title", _) => self.title.clone(),
+            ("generated", _) => self.generated.format("%m-%d-%Y %H:%M").to_string(),
+            ("count", _) => self.jotforms.len().to_string(),
+            (_, Some(j)) => match name {
+                "id" => j.id.clone(),
+                "submitter" => format!("{} {}", j.submitter_name.first, j.submitter_name.last)
+                    .trim()
+                    .to_string(),
+                "submitted" => j
+                    .created_at
+                    .timestamp()
+                    .map(|at| at.format("%m-%d-%Y %H:%M").to_string())
+                    .unwrap_or_else(|| format!("{} {}", j.created_at.date, j.created_at.time)),
+                "location" => j.location.clone(),
+                "exhibit" => j.exhibit_name.clone(),
+                "priority" => j.priority_level.to_string(),
+                "department" => j.department.to_string(),
+                "status" => j.status.to_string(),
+                "assignee" => j.assignee.clone().unwrap_or_default(),
+                "description" => j.description.trim_end().to_string(),
+                _ => unreachable!("checked when parsed"),
+            },
+            (_, None) => unreachable!("checked when parsed"),
+        }
+    }
+}
+
+/// The HTML and plain-text templates, from the configured directory where
+/// it has them and built in otherwise.
+#[derive(Debug, Clone)]
+pub struct Templates {
+    pub html: Template,
+    pub text: Template,
+}
+
+impl Templates {
+    pub fn load(dir: Option<&Path>) -> Result<Self> {
+        Ok(Self {
+            html: load(dir, "work_order.html", HTML_TEMPLATE)?,
+            text: load(dir, "work_order.txt", TEXT_TEMPLATE)?,
+        })
+    }
+}
+
+fn load(dir: Option<&Path>, name: &str, builtin: &str) -> Result<Template> {
+    match dir.map(|dir| dir.join(name)).filter(|path| path.exists()) {
+        Some(path) => {
+            let source = fs::read_to_string(&path).map_err(|source| JotviewError::Io {
+                path: path.display().to_string(),
+                source,
+            })?;
+            Template::parse(&path.display().to_string(), &source)
+        }
+        None => Template::parse(name, builtin),
+    }
+}
+
+/// Writes `report` as an HTML file and a text file side by side in the
+/// configured directory, replacing earlier copies, and returns their paths.
+pub fn write(config: &ReportConfig, report: &Report) -> Result<[PathBuf; 2]> {
+    let templates = Templates::load(config.templates.as_deref())?;
+    let dir = config.dir.clone().unwrap_or_default();
+    let stem = report.file_stem();
+    let html = dir.join(format!("{}.html", stem));
+    let text = dir.join(format!("{}.txt", stem));
+    for (path, contents) in [
+        (&html, templates.html.render(report, escape_html)),
+        (&text, templates.text.render(report, str::to_string)),
+    ] {
+        fs::write(path, contents).map_err(|source| JotviewError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+    }
+    Ok([html, text])
+}
+
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        model::Status,
+        test_support::{jotform, submitted},
+    };
+    use chrono::NaiveDate;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 5, 10)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn templates_repeat_per_ticket_and_escape_html() {
+        let template = Template::parse(
+            "test",
+            "<h1>{{title}} ({{count}})</h1>\n{{#tickets}}\n<p>#{{id}} {{ description }}</p>\n{{/tickets}}\nend",
+        )
+        .unwrap();
+        // Work orders list the oldest first; #1 is from 2024-05-01.
+        let ticket = |id, priority, status| Jotform {
+            created_at: submitted(&format!("2024-05-0{}", id)),
+            description: "Lamp <b>out</b> & flickering".to_string(),
+            priority_level: priority,
+            status,
+            ..jotform(id)
+        };
+        let jotforms = [
+            ticket("3", Priority::High, Status::Open),
+            ticket("1", Priority::High, Status::InProgress),
+            ticket("2", Priority::Low, Status::Open),
+            ticket("4", Priority::High, Status::Closed),
+        ];
+        let report = Report::high_priority(&jotforms, now());
+        assert_eq!(
+            template.render(&report, escape_html),
+            "<h1>High-priority work orders (2)</h1>\n\
+             <p>#1 Lamp &lt;b&gt;out&lt;/b&gt; &amp; flickering</p>\n\
+             <p>#3 Lamp &lt;b&gt;out&lt;/b&gt; &amp; flickering</p>\n\
+             end"
+        );
+        assert_eq!(report.file_stem(), "work-orders-20240510-080000");
+
+        let builtin = Templates::load(None).unwrap();
+        let single = Report::single(&jotforms[2], now());
+        let text = builtin.text.render(&single, str::to_string);
+        assert!(text.starts_with("WORK ORDER #2"), "{text}");
+        assert!(text.contains("Submitted by  Ann Lee\n"), "{text}");
+        assert!(builtin
+            .html
+            .render(&single, escape_html)
+            .contains("<title>Work order #2</title>"));
+    }
+
+    #[test]
+    fn template_mistakes_are_reported() {
+        let reason = |source| match Template::parse("t.html", source) {
+            Err(JotviewError::Format { reason, .. }) => reason,
+            other => panic!("{other:?}"),
+        };
+        assert_eq!(reason("{{titel}}"), "unknown placeholder {{titel}}");
+        assert_eq!(reason("{{id}}"), "{{id}} is only known inside {{#tickets}}");
+        assert_eq!(reason("{{#tickets}}{{id}}"), "{{#tickets}} is never closed");
+        assert_eq!(reason("{{/tickets}}"), "{{/tickets}} without {{#tickets}}");
+        assert_eq!(reason("a {{b"), "a \"{{\" is never closed");
+    }
+}
@@ -0,0 +1,402 @@
+//! `jotview list`, `show`, `set-status`, `export` and `work-order`: the
+//! backend without the TUI, for shell scripts and cron jobs.
+
+use chrono::Local;
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use crate::{
+    backend::TicketBackend,
+    cli::{CliCommand, Output, TicketFilter},
+    config::{Config, ReportConfig},
+    error::JotviewError,
+    export::{self, csv, Format},
+    filter::{DateRange, Filter},
+    model::Jotform,
+    report::{self, Report},
+    sort::SortOrder,
+};
+
+/// The backend couldn't be reached or refused the request.
+const EXIT_BACKEND: u8 = 1;
+/// Bad flags or config; clap uses the same status for its own errors.
+const EXIT_USAGE: u8 = 2;
+const EXIT_NOT_FOUND: u8 = 3;
+/// The ticket changed while `set-status` was saving it.
+const EXIT_CONFLICT: u8 = 4;
+
+const COLUMNS: [&str; 9] = [
+    "ID",
+    "Submitter",
+    "Date",
+    "Location",
+    "Exhibit",
+    "Priority",
+    "Department",
+    "Status",
+    "Assignee",
+];
+
+/// Runs `command` and writes what it prints to `out`.
+pub async fn run(
+    command: CliCommand,
+    output: Output,
+    config: &Config,
+    backend: &dyn TicketBackend,
+    out: &mut impl Write,
+) -> ExitCode {
+    let text = match command {
+        CliCommand::List(filter) => match tickets(&filter, config, backend).await {
+            Ok(jotforms) => Ok(list(&jotforms, output)),
+            Err(code) => return code,
+        },
+        CliCommand::Export {
+            filter,
+            format,
+            file,
+        } => match tickets(&filter, config, backend).await {
+            Ok(jotforms) => return export(&jotforms, format, file, out),
+            Err(code) => return code,
+        },
+        CliCommand::WorkOrder { id, dir } => {
+            return work_order(id, dir, config, backend, out).await;
+        }
+        CliCommand::Show { id } => backend.get(&id).await.map(|j| show(&j, output)),
+        CliCommand::SetStatus { id, status } => {
+            // The revision just read guards against a change made meanwhile.
+            match backend.get(&id).await {
+                Ok(current) => backend
+                    .update_status(&id, &status, current.updated_at.as_deref())
+                    .await
+                    .map(|stored| {
+                        let stored = stored.unwrap_or_else(|| Jotform {
+                            status: status.clone(),
+                            ..current.clone()
+                        });
+                        match output {
+                            Output::Table => {
+                                format!("{}: {} -> {}\n", id, current.status, stored.status)
+                            }
+                            _ => list(std::slice::from_ref(&stored), output),
+                        }
+                    }),
+                Err(e) => Err(e),
+            }
+        }
+    };
+
+    match text {
+        Ok(text) => print(out, &text),
+        Err(e) => failure(&e),
+    }
+}
+
+/// The tickets `filter` lets through, in the TUI's default order. Failures
+/// are reported, and come back as the exit status to give up with.
+async fn tickets(
+    filter: &TicketFilter,
+    config: &Config,
+    backend: &dyn TicketBackend,
+) -> Result<Vec<Jotform>, ExitCode> {
+    let mut wanted = Filter {
+        statuses: filter.status.iter().cloned().collect(),
+        priorities: filter.priority.iter().cloned().collect(),
+        departments: filter.department.iter().cloned().collect(),
+        ..Filter::default()
+    };
+    if let Some(date) = &filter.date {
+        match DateRange::parse(date, Local::now().date_naive()) {
+            Ok(range) => wanted.date_range = Some(range),
+            Err(e) => return Err(usage_error(&format!("invalid --date: {}", e))),
+        }
+    }
+    if filter.mine {
+        match &config.user {
+            Some(user) => wanted.assignee = Some(user.clone()),
+            None => {
+                return Err(usage_error(
+                    "--mine needs your name; set user or pass --user",
+                ))
+            }
+        }
+    }
+    let mut jotforms = backend.list().await.map_err(|e| failure(&e))?;
+    jotforms.retain(|j| wanted.matches(j));
+    SortOrder::default().apply(&mut jotforms);
+    Ok(jotforms)
+}
+
+/// Writes the export to `file`, or to `out` for `-`, and says where it went.
+fn export(
+    jotforms: &[Jotform],
+    format: Format,
+    file: Option<PathBuf>,
+    out: &mut impl Write,
+) -> ExitCode {
+    let rows: Vec<&Jotform> = jotforms.iter().collect();
+    let path = file.unwrap_or_else(|| {
+        PathBuf::from(export::default_file_name(
+            format,
+            Local::now().naive_local(),
+        ))
+    });
+    let written = if path.as_os_str() == "-" {
+        out.write_all(format.render(&rows).as_bytes())
+    } else {
+        export::write(&path, format, &rows).and_then(|()| {
+            writeln!(
+                out,
+                "Exported {} ticket(s) to {}",
+                rows.len(),
+                path.display()
+            )
+        })
+    };
+    match written {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+            eprintln!("jotview: could not write {}: {}", path.display(), e);
+            ExitCode::FAILURE
+        }
+        _ => ExitCode::SUCCESS,
+    }
+}
+
+/// Writes work orders for ticket `id`, or the open High-priority ones, and
+/// prints the files' paths.
+async fn work_order(
+    id: Option<String>,
+    dir: Option<PathBuf>,
+    config: &Config,
+    backend: &dyn TicketBackend,
+    out: &mut impl Write,
+) -> ExitCode {
+    let jotforms = match &id {
+        Some(id) => backend.get(id).await.map(|jotform| vec![jotform]),
+        None => backend.list().await,
+    };
+    let jotforms = match jotforms {
+        Ok(jotforms) => jotforms,
+        Err(e) => return failure(&e),
+    };
+    let now = Local::now().naive_local();
+    let report = match id {
+        Some(_) => Report::single(&jotforms[0], now),
+        None => Report::high_priority(&jotforms, now),
+    };
+    let text = if report.jotforms.is_empty() {
+        "No open or in-progress High-priority tickets\n".to_string()
+    } else {
+        let settings = ReportConfig {
+            dir: dir.or_else(|| config.report.dir.clone()),
+            ..config.report.clone()
+        };
+        match report::write(&settings, &report) {
+            Ok(paths) => paths.map(|path| format!("{}\n", path.display())).concat(),
+            Err(e) => return failure(&e),
+        }
+    };
+    print(out, &text)
+}
+
+fn print(out: &mut impl Write, text: &str) -> ExitCode {
+    match out.write_all(text.as_bytes()) {
+        // A closed pipe (`jotview list | head`) isn't a failure.
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+            eprintln!("jotview: {}", e);
+            ExitCode::FAILURE
+        }
+        _ => ExitCode::SUCCESS,
+    }
+}
+
+fn usage_error(message: &str) -> ExitCode {
+    eprintln!("jotview: {}", message);
+    ExitCode::from(EXIT_USAGE)
+}
+
+fn failure(error: &JotviewError) -> ExitCode {
+    eprintln!("jotview: {}", error);
+    ExitCode::from(exit_status(error))
+}
+
+fn exit_status(error: &JotviewError) -> u8 {
+    match error {
+        JotviewError::NotFound { .. } => EXIT_NOT_FOUND,
+        JotviewError::Conflict { .. } => EXIT_CONFLICT,
+        _ => EXIT_BACKEND,
+    }
+}
+
+/// The table's columns, plus the id scripts need to act on a row.
+fn row(jotform: &Jotform) -> [String; COLUMNS.len()] {
+    let [submitter, date, location, exhibit, priority, department, status, assignee, _] =
+        export::cells(jotform);
+    [
+        jotform.id.clone(),
+        submitter,
+        date,
+        location,
+        exhibit,
+        priority,
+        department,
+        status,
+        assignee,
+    ]
+}
+
+fn list(jotforms: &[Jotform], output: Output) -> String {
+    match output {
+        Output::Table => table(jotforms),
+        Output::Json => json(&jotforms),
+        Output::Csv => {
+            let rows = jotforms.iter().map(|j| row(j).to_vec());
+            csv(std::iter::once(COLUMNS.map(String::from).to_vec()).chain(rows))
+        }
+    }
+}
+
+fn show(jotform: &Jotform, output: Output) -> String {
+    match output {
+        Output::Table => details(jotform),
+        Output::Json => json(jotform),
+        Output::Csv => {
+            let mut header = COLUMNS.map(String::from).to_vec();
+            header.push("Description".to_string());
+            let mut values = row(jotform).to_vec();
+            values.push(jotform.description.clone());
+            csv([header, values])
+        }
+    }
+}
+
+/// Columns padded to their widest value, without trailing blanks.
+fn table(jotforms: &[Jotform]) -> String {
+    let rows: Vec<[String; COLUMNS.len()]> = jotforms.iter().map(row).collect();
+    let mut widths = COLUMNS.map(|title| title.chars().count());
+    for row in &rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+    let mut text = String::new();
+    for row in std::iter::once(COLUMNS.map(String::from)).chain(rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(value, width)| format!("{:<width$}", value, width = width))
+            .collect();
+        text.push_str(cells.join("  ").trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// Every field, laid out like the detail view.
+fn details(jotform: &Jotform) -> String {
+    let submitted = jotform
+        .created_at
+        .timestamp()
+        .map(|at| at.format("%m-%d-%Y %H:%M").to_string())
+        .unwrap_or_else(|| format!("{} {}", jotform.created_at.date, jotform.created_at.time));
+    let fields = [
+        ("ID", jotform.id.clone()),
+        ("Status", jotform.status.to_string()),
+        ("Priority", jotform.priority_level.to_string()),
+        ("Department", jotform.department.to_string()),
+        (
+            "Submitter",
+            format!(
+                "{} {}",
+                jotform.submitter_name.first, jotform.submitter_name.last
+            ),
+        ),
+        ("Submitted", submitted),
+        ("Location", jotform.location.clone()),
+        ("Exhibit", jotform.exhibit_name.clone()),
+        (
+            "Assignee",
+            jotform
+                .assignee
+                .clone()
+                .unwrap_or_else(|| "Unassigned".to_string()),
+        ),
+    ];
+    let mut text = String::new();
+    for (name, value) in fields {
+        text.push_str(format!("{:<12}{}", name, value).trim_end());
+        text.push('\n');
+    }
+    if !jotform.description.is_empty() {
+        text.push('\n');
+        text.push_str(jotform.description.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+fn json(value: &impl serde::Serialize) -> String {
+    serde_json::to_string_pretty(value).expect("tickets serialize") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::Status, test_support::jotform};
+
+    #[test]
+    fn table_pads_columns_and_csv_quotes_what_needs_it() {
+        let jotforms = [
+            jotform("7"),
+            Jotform {
+                location: "Hall \"B\", east".to_string(),
+                ..jotform("12")
+            },
+        ];
+        assert_eq!(
+            list(&jotforms, Output::Table),
+            "ID  Submitter  Date        Location        Exhibit    Priority  Department  Status  Assignee\n\
+             7   Ann        05-01-2024  Lobby           Dinosaurs  Low       Exhibits    Open\n\
+             12  Ann        05-01-2024  Hall \"B\", east  Dinosaurs  Low       Exhibits    Open\n"
+        );
+        assert_eq!(
+            list(&jotforms, Output::Csv),
+            "ID,Submitter,Date,Location,Exhibit,Priority,Department,Status,Assignee\r\n\
+             7,Ann,05-01-2024,Lobby,Dinosaurs,Low,Exhibits,Open,\r\n\
+             12,Ann,05-01-2024,\"Hall \"\"B\"\", east\",Dinosaurs,Low,Exhibits,Open,\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_map_to_exit_statuses() {
+        use crate::backend::MemoryBackend;
+
+        let backend = MemoryBackend::new(vec![jotform("7")]);
+        let config = Config::in_memory();
+        let set = |id: &str| CliCommand::SetStatus {
+            id: id.to_string(),
+            status: Status::Closed,
+        };
+        let mut out = Vec::new();
+        assert_eq!(
+            run(set("7"), Output::Table, &config, &backend, &mut out).await,
+            ExitCode::SUCCESS
+        );
+        assert_eq!(String::from_utf8(out).unwrap(), "7: Open -> Closed\n");
+        assert_eq!(backend.get("7").await.unwrap().status, Status::Closed);
+        assert_eq!(
+            run(set("8"), Output::Table, &config, &backend, &mut Vec::new()).await,
+            ExitCode::from(EXIT_NOT_FOUND)
+        );
+        let mine = CliCommand::List(TicketFilter {
+            mine: true,
+            ..TicketFilter::default()
+        });
+        assert_eq!(
+            run(mine, Output::Table, &config, &backend, &mut Vec::new()).await,
+            ExitCode::from(EXIT_USAGE)
+        );
+    }
+}
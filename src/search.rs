@@ -0,0 +1,146 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use std::ops::Range;
+
+use crate::model::Jotform;
+
+/// Free-text search over the loaded jotforms, driven by `/`.
+#[derive(Debug, Default)]
+pub struct Search {
+    pub query: String,
+    /// Whether the input line has focus and is receiving keystrokes.
+    pub editing: bool,
+}
+
+impl Search {
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.editing = false;
+    }
+
+    /// Case-insensitive match across every searchable field.
+    pub fn matches(&self, jotform: &Jotform) -> bool {
+        if !self.is_active() {
+            return true;
+        }
+        [
+            jotform.submitter_name.first.as_str(),
+            jotform.submitter_name.last.as_str(),
+            jotform.location.as_str(),
+            jotform.exhibit_name.as_str(),
+            jotform.department.as_str(),
+            jotform.description.as_str(),
+            jotform.assignee.as_deref().unwrap_or_default(),
+        ]
+        .iter()
+        .any(|field| !find_matches(field, &self.query).is_empty())
+    }
+
+    /// Splits `text` into spans, marking every occurrence of the query.
+    pub fn highlight<'a>(&self, text: &'a str, style: Style) -> Line<'a> {
+        let matches = if self.is_active() {
+            find_matches(text, &self.query)
+        } else {
+            Vec::new()
+        };
+
+        let mut spans = Vec::with_capacity(matches.len() * 2 + 1);
+        let mut cursor = 0;
+        for range in matches {
+            if range.start > cursor {
+                spans.push(Span::styled(&text[cursor..range.start], style));
+            }
+            spans.push(Span::styled(&text[range.clone()], highlight_style()));
+            cursor = range.end;
+        }
+        if cursor < text.len() || spans.is_empty() {
+            spans.push(Span::styled(&text[cursor..], style));
+        }
+        Line::from(spans)
+    }
+}
+
+pub fn highlight_style() -> Style {
+    Style::default()
+        .fg(Color::Rgb(30, 30, 40))
+        .bg(Color::Rgb(255, 255, 153))
+}
+
+/// Byte ranges in `haystack` of non-overlapping, case-insensitive occurrences
+/// of `needle`.
+fn find_matches(haystack: &str, needle: &str) -> Vec<Range<usize>> {
+    let needle = needle.to_lowercase();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    // Lowercasing can change byte lengths, so remember where each byte of the
+    // lowercased text came from in the original.
+    let mut lowered = String::with_capacity(haystack.len());
+    let mut origin = Vec::with_capacity(haystack.len() + 1);
+    for (index, ch) in haystack.char_indices() {
+        for lower in ch.to_lowercase() {
+            let before = lowered.len();
+            lowered.push(lower);
+            origin.extend(std::iter::repeat_n(index, lowered.len() - before));
+        }
+    }
+    origin.push(haystack.len());
+
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    while let Some(found) = lowered[from..].find(&needle) {
+        let start = from + found;
+        let end = start + needle.len();
+        // Round the end up to the start of the next original character.
+        let original_end = origin[end..]
+            .iter()
+            .copied()
+            .find(|&i| i > origin[end - 1])
+            .unwrap_or(haystack.len());
+        ranges.push(origin[start]..original_end);
+        from = end;
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_case_insensitive_ranges() {
+        assert_eq!(
+            find_matches("Broken Lamp, lamp", "LAMP"),
+            vec![7..11, 13..17]
+        );
+        assert!(find_matches("Broken Lamp", "bulb").is_empty());
+    }
+
+    #[test]
+    fn ranges_respect_multibyte_characters() {
+        let text = "Café ÉCLAIR";
+        let ranges = find_matches(text, "é");
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(&text[ranges[0].clone()], "é");
+        assert_eq!(&text[ranges[1].clone()], "É");
+    }
+
+    #[test]
+    fn highlight_splits_around_matches() {
+        let search = Search {
+            query: "amp".to_string(),
+            editing: false,
+        };
+        let line = search.highlight("Lamp", Style::default());
+        let parts: Vec<&str> = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(parts, vec!["L", "amp"]);
+        assert_eq!(line.spans[1].style, highlight_style());
+    }
+}
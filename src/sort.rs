@@ -0,0 +1,219 @@
+use std::cmp::Ordering;
+
+use crate::model::Jotform;
+
+/// A table column the list can be ordered by, in header order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Submitter,
+    Date,
+    Location,
+    Exhibit,
+    Priority,
+    Department,
+    Status,
+    Assignee,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// How the table is ordered. The default reproduces the original grouping:
+/// in-progress tickets first, unplanned last, newest first within a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOrder {
+    pub column: SortColumn,
+    pub direction: SortDirection,
+}
+
+impl SortColumn {
+    pub const ALL: [SortColumn; 8] = [
+        SortColumn::Submitter,
+        SortColumn::Date,
+        SortColumn::Location,
+        SortColumn::Exhibit,
+        SortColumn::Priority,
+        SortColumn::Department,
+        SortColumn::Status,
+        SortColumn::Assignee,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            SortColumn::Submitter => "Submitter",
+            SortColumn::Date => "Date",
+            SortColumn::Location => "Location",
+            SortColumn::Exhibit => "Exhibit",
+            SortColumn::Priority => "Priority",
+            SortColumn::Department => "Department",
+            SortColumn::Status => "Status",
+            SortColumn::Assignee => "Assignee",
+        }
+    }
+
+    pub fn next(self) -> SortColumn {
+        let index = SortColumn::ALL.iter().position(|&c| c == self).unwrap_or(0);
+        SortColumn::ALL[(index + 1) % SortColumn::ALL.len()]
+    }
+
+    fn compare(self, a: &Jotform, b: &Jotform) -> Ordering {
+        match self {
+            SortColumn::Submitter => compare_text(
+                &format!("{} {}", a.submitter_name.first, a.submitter_name.last),
+                &format!("{} {}", b.submitter_name.first, b.submitter_name.last),
+            ),
+            SortColumn::Date => compare_submitted(a, b),
+            SortColumn::Location => compare_text(&a.location, &b.location),
+            SortColumn::Exhibit => compare_text(&a.exhibit_name, &b.exhibit_name),
+            SortColumn::Priority => a
+                .priority_level
+                .sort_rank()
+                .cmp(&b.priority_level.sort_rank()),
+            SortColumn::Department => compare_text(a.department.as_str(), b.department.as_str()),
+            SortColumn::Status => a.status.sort_rank().cmp(&b.status.sort_rank()),
+            SortColumn::Assignee => compare_assignee(a, b),
+        }
+    }
+}
+
+impl SortDirection {
+    pub fn toggle(self) -> SortDirection {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+
+    pub fn arrow(self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "▲",
+            SortDirection::Descending => "▼",
+        }
+    }
+}
+
+impl Default for SortOrder {
+    fn default() -> Self {
+        SortOrder {
+            column: SortColumn::Status,
+            direction: SortDirection::Ascending,
+        }
+    }
+}
+
+impl SortOrder {
+    /// Orders by the chosen column, then newest submission first, then id,
+    /// so rows with equal keys keep a predictable position. Only the primary
+    /// key follows `direction`.
+    pub fn compare(&self, a: &Jotform, b: &Jotform) -> Ordering {
+        let primary = self.column.compare(a, b);
+        let primary = match self.direction {
+            SortDirection::Ascending => primary,
+            SortDirection::Descending => primary.reverse(),
+        };
+        primary
+            .then_with(|| newest_first(a, b))
+            .then_with(|| a.id.cmp(&b.id))
+    }
+
+    pub fn apply(&self, jotforms: &mut [Jotform]) {
+        jotforms.sort_by(|a, b| self.compare(a, b));
+    }
+
+    /// Inserts into a list already in this order, keeping it sorted.
+    pub fn insert(&self, jotforms: &mut Vec<Jotform>, jotform: Jotform) {
+        let index = jotforms.partition_point(|j| self.compare(j, &jotform).is_lt());
+        jotforms.insert(index, jotform);
+    }
+}
+
+fn compare_text(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase())
+}
+
+/// By name; unassigned tickets sort after assigned ones.
+fn compare_assignee(a: &Jotform, b: &Jotform) -> Ordering {
+    match (&a.assignee, &b.assignee) {
+        (Some(a), Some(b)) => compare_text(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Oldest first; submissions with an unparseable date sort after all others.
+fn compare_submitted(a: &Jotform, b: &Jotform) -> Ordering {
+    match (a.created_at.timestamp(), b.created_at.timestamp()) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Newest first; submissions with an unparseable date still sort last.
+fn newest_first(a: &Jotform, b: &Jotform) -> Ordering {
+    match (a.created_at.timestamp(), b.created_at.timestamp()) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        _ => compare_submitted(a, b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, submitted};
+
+    fn jotform(id: &str, date: &str, status: &str, priority: &str) -> Jotform {
+        Jotform {
+            created_at: submitted(date),
+            priority_level: priority.to_string().into(),
+            status: status.to_string().into(),
+            ..test_support::jotform(id)
+        }
+    }
+
+    fn ids(jotforms: &[Jotform]) -> Vec<&str> {
+        jotforms.iter().map(|j| j.id.as_str()).collect()
+    }
+
+    #[test]
+    fn default_groups_by_status_then_newest_first() {
+        let mut jotforms = vec![
+            jotform("old-open", "2024-01-01", "Open", "Low"),
+            jotform("unplanned", "2024-03-01", "Unplanned", "Low"),
+            jotform("new-closed", "2024-02-01", "Closed", "Low"),
+            jotform("bad-date", "soon", "Open", "Low"),
+            jotform("working", "2023-12-01", "InProgress", "Low"),
+        ];
+        SortOrder::default().apply(&mut jotforms);
+        assert_eq!(
+            ids(&jotforms),
+            ["working", "new-closed", "old-open", "bad-date", "unplanned"]
+        );
+    }
+
+    #[test]
+    fn direction_only_flips_the_primary_key() {
+        let mut jotforms = vec![
+            jotform("low", "2024-01-01", "Open", "Low"),
+            jotform("high-old", "2024-01-01", "Open", "High"),
+            jotform("high-new", "2024-02-01", "Open", "High"),
+        ];
+        let order = SortOrder {
+            column: SortColumn::Priority,
+            direction: SortDirection::Descending,
+        };
+        order.apply(&mut jotforms);
+        assert_eq!(ids(&jotforms), ["high-new", "high-old", "low"]);
+
+        order.insert(
+            &mut jotforms,
+            jotform("medium", "2024-01-01", "Open", "Medium"),
+        );
+        assert_eq!(ids(&jotforms), ["high-new", "high-old", "medium", "low"]);
+    }
+}
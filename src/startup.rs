@@ -0,0 +1,58 @@
+use chrono::{DateTime, Local};
+use std::time::Duration;
+use tokio::time::Instant;
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// What the app shows before the jotforms list is first loaded.
+#[derive(Debug)]
+pub enum Startup {
+    /// The first (or a retried) fetch is in flight.
+    Connecting { attempt: u32, auto_retry: bool },
+    /// The last fetch failed; the connection error screen is shown.
+    Failed(ConnectionFailure),
+    /// The list is loaded (from the backend or the offline cache).
+    Done,
+}
+
+#[derive(Debug)]
+pub struct ConnectionFailure {
+    pub url: String,
+    pub error: String,
+    /// Failed attempts so far, starting at 1.
+    pub attempt: u32,
+    /// When the next automatic retry fires. Automatic retries start once
+    /// the user has asked for a retry with `r`.
+    pub retry_at: Option<Instant>,
+    /// When the offline copy was saved and how many jotforms it holds.
+    pub offline_copy: Option<(DateTime<Local>, usize)>,
+}
+
+impl Startup {
+    pub fn is_done(&self) -> bool {
+        matches!(self, Startup::Done)
+    }
+}
+
+impl ConnectionFailure {
+    /// Exponential backoff after `attempt` failures: 1s, 2s, 4s, … capped at a minute.
+    pub fn backoff(attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        INITIAL_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_a_minute() {
+        let delays: Vec<u64> = (1..=8)
+            .map(|attempt| ConnectionFailure::backoff(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(ConnectionFailure::backoff(u32::MAX), MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::model::{Jotform, Priority, Status};
+
+/// Popup for moving the selected jotform straight to any status.
+#[derive(Debug, Default)]
+pub struct StatusMenu {
+    pub open: bool,
+    /// Index into `Status::KNOWN`.
+    pub cursor: usize,
+    /// A chosen status waiting on a y/n answer before it is saved.
+    pub confirming: Option<Status>,
+}
+
+impl StatusMenu {
+    /// Opens the menu with the cursor on the jotform's current status.
+    pub fn open_at(&mut self, current: &Status) {
+        self.open = true;
+        self.cursor = current.rank().min(Status::KNOWN.len() - 1);
+        self.confirming = None;
+    }
+
+    pub fn close(&mut self) {
+        *self = StatusMenu::default();
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        let last = Status::KNOWN.len() as isize - 1;
+        self.cursor = (self.cursor as isize + delta).clamp(0, last) as usize;
+    }
+
+    pub fn highlighted(&self) -> &'static Status {
+        &Status::KNOWN[self.cursor]
+    }
+}
+
+/// The status picked by a number key; `1` is the first entry in the menu.
+pub fn shortcut(key: char) -> Option<&'static Status> {
+    let index = key.to_digit(10)?.checked_sub(1)?;
+    Status::KNOWN.get(index as usize)
+}
+
+/// Closing a High-priority ticket is easy to do by accident and expensive
+/// to miss, so it is confirmed first.
+pub fn needs_confirmation(jotform: &Jotform, target: &Status) -> bool {
+    *target == Status::Closed
+        && jotform.status != Status::Closed
+        && jotform.priority_level == Priority::High
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::jotform;
+
+    #[test]
+    fn number_keys_follow_menu_order() {
+        assert_eq!(shortcut('1'), Some(&Status::Open));
+        assert_eq!(shortcut('4'), Some(&Status::Unplanned));
+        assert_eq!(shortcut('0'), None);
+        assert_eq!(shortcut('9'), None);
+        assert_eq!(shortcut('x'), None);
+    }
+
+    #[test]
+    fn only_closing_high_priority_tickets_is_confirmed() {
+        let mut jotform = Jotform {
+            priority_level: Priority::High,
+            ..jotform("1")
+        };
+        assert!(needs_confirmation(&jotform, &Status::Closed));
+        assert!(!needs_confirmation(&jotform, &Status::Unplanned));
+
+        jotform.priority_level = Priority::Medium;
+        assert!(!needs_confirmation(&jotform, &Status::Closed));
+    }
+}
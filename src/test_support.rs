@@ -0,0 +1,39 @@
+//! Fixtures shared by the unit tests.
+
+use crate::model::{Department, FullName, Jotform, Priority, Status, SubmissionDate};
+
+/// An open, low-priority Exhibits ticket from Ann Lee about a lamp in the
+/// Lobby's Dinosaurs exhibit, submitted 2024-05-01 at 09:30, unassigned and
+/// without a revision. Tests set the fields they look at with struct update
+/// syntax, as in `Jotform { status: Status::Closed, ..jotform("2") }`.
+pub fn jotform(id: &str) -> Jotform {
+    Jotform {
+        id: id.to_string(),
+        submitter_name: name("Ann"),
+        created_at: submitted("2024-05-01"),
+        location: "Lobby".to_string(),
+        exhibit_name: "Dinosaurs".to_string(),
+        description: "Lamp out".to_string(),
+        priority_level: Priority::Low,
+        department: Department::Exhibits,
+        status: Status::Open,
+        assignee: None,
+        updated_at: None,
+    }
+}
+
+/// `first` Lee.
+pub fn name(first: &str) -> FullName {
+    FullName {
+        first: first.to_string(),
+        last: "Lee".to_string(),
+    }
+}
+
+/// 09:30 on `date`, which is kept as given, parseable or not.
+pub fn submitted(date: &str) -> SubmissionDate {
+    SubmissionDate {
+        date: date.to_string(),
+        time: "09:30".to_string(),
+    }
+}
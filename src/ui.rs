@@ -0,0 +1,1765 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    prelude::{Buffer, StatefulWidget},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{
+        Bar, BarChart, BarGroup, Block, Borders, Cell, Clear, Padding, Paragraph, Row, Scrollbar,
+        ScrollbarState, Table, TableState, Widget, Wrap,
+    },
+};
+
+use crate::{
+    app::{App, ErrorPopup},
+    conflict::{Conflict, ConflictField},
+    dashboard::{self, Stats},
+    edit_form::{EditField, EditForm},
+    export::{ExportPrompt, Format},
+    filter::{self, FilterItem},
+    model::Status,
+    new_ticket::{NewTicketForm, TicketField},
+    notes::{DetailPane, NoteInput},
+    search::highlight_style,
+    sort::SortColumn,
+    startup::Startup,
+};
+use tokio::time::Instant;
+
+impl Widget for &App {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if !self.startup.is_done() {
+            self.render_startup(area, buf);
+            if let Some(error) = self.errors.front() {
+                self.render_error_popup(error, area, buf);
+            }
+            return;
+        }
+        if self.dashboard_open {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(1)])
+                .split(area);
+            self.render_dashboard(chunks[0], buf);
+            self.render_status_bar(chunks[1], buf);
+            self.render_popups(area, buf);
+            return;
+        }
+        if self.detail_open {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(1)])
+                .split(area);
+            self.render_detail(chunks[0], buf);
+            self.render_status_bar(chunks[1], buf);
+            self.render_popups(area, buf);
+            return;
+        }
+
+        let show_search = self.search.editing || self.search.is_active();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(70),
+                Constraint::Length(if show_search { 1 } else { 0 }),
+                Constraint::Percentage(30),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        let table_area = if self.filter_panel.open {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(32), Constraint::Min(0)])
+                .split(chunks[0]);
+            self.render_filter_panel(columns[0], buf);
+            columns[1]
+        } else {
+            chunks[0]
+        };
+
+        let visible = self.visible_jotforms();
+
+        // Render the table
+        let rows = visible.iter().map(|jotform| {
+            let is_selected = jotform.id == self.selected_id;
+            let formatted_date = jotform
+                .created_at
+                .parsed_date()
+                .map(|date| date.format("%m-%d-%Y").to_string())
+                .unwrap_or_else(|_| jotform.created_at.date.clone());
+
+            let status_style = Style::default().fg(jotform.status.color());
+            let priority_style = Style::default().fg(jotform.priority_level.color());
+            let department_style = Style::default().fg(jotform.department.color());
+            let row_style = if is_selected {
+                Style::default().bg(Color::Rgb(70, 70, 90))
+            } else {
+                Style::default().bg(Color::Rgb(30, 30, 40))
+            };
+
+            Row::new(vec![
+                Cell::from(
+                    self.search
+                        .highlight(&jotform.submitter_name.first, Style::default()),
+                ),
+                Cell::from(formatted_date),
+                Cell::from(self.search.highlight(&jotform.location, Style::default())),
+                Cell::from(
+                    self.search
+                        .highlight(&jotform.exhibit_name, Style::default()),
+                ),
+                Cell::from(Span::styled(
+                    jotform.priority_level.as_str(),
+                    priority_style,
+                )),
+                Cell::from(
+                    self.search
+                        .highlight(jotform.department.as_str(), department_style),
+                ),
+                Cell::from(Span::styled(jotform.status.as_str(), status_style)),
+                match &jotform.assignee {
+                    Some(assignee) => Cell::from(self.search.highlight(assignee, Style::default())),
+                    None => Cell::from(Span::styled(
+                        "—",
+                        Style::default().fg(Color::Rgb(150, 150, 170)),
+                    )),
+                },
+            ])
+            .style(row_style)
+        });
+
+        let table = Table::new(rows, [Constraint::Percentage(12); SortColumn::ALL.len()])
+            .header(
+                Row::new(SortColumn::ALL.map(|column| {
+                    if column == self.sort.column {
+                        format!("{} {}", column.title(), self.sort.direction.arrow())
+                    } else {
+                        column.title().to_string()
+                    }
+                }))
+                .style(
+                    Style::default()
+                        .fg(Color::Rgb(200, 200, 200))
+                        .bg(Color::Rgb(50, 50, 60))
+                        .add_modifier(Modifier::BOLD),
+                ),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+                    .title(self.table_title(visible.len()))
+                    .title_bottom(key_hints(&[
+                        ("Enter", "Open"),
+                        ("E", "Status"),
+                        ("C", "Edit"),
+                        ("A", "Assign"),
+                        ("N", "New"),
+                        ("/", "Search"),
+                        ("F", "Filter"),
+                        ("M", "Mine"),
+                        ("S", "Sort"),
+                        ("X", "Export"),
+                        ("R", "Refresh"),
+                        ("Q", "Quit"),
+                    ]))
+                    .title_style(
+                        Style::default()
+                            .fg(Color::Rgb(150, 150, 170))
+                            .add_modifier(Modifier::BOLD),
+                    ),
+            )
+            .column_spacing(2);
+
+        // Start from last frame's offset; ratatui scrolls just enough to keep
+        // the selected row on screen.
+        let mut table_state = TableState::default()
+            .with_offset(self.table_offset.get())
+            .with_selected(visible.iter().position(|j| j.id == self.selected_id));
+        StatefulWidget::render(table, table_area, buf, &mut table_state);
+        self.table_offset.set(table_state.offset());
+
+        if show_search {
+            self.render_search_bar(visible.len(), chunks[1], buf);
+        }
+
+        let selected_jotform = visible.iter().find(|j| j.id == self.selected_id);
+        let description = match selected_jotform {
+            Some(j) => j.description.as_str(),
+            None if self.search.is_active() && visible.is_empty() => "No Jotforms match the search",
+            None => "Select a Jotform to view description",
+        };
+
+        self.render_description(description, false, chunks[2], buf);
+
+        self.render_status_bar(chunks[3], buf);
+        self.render_popups(area, buf);
+    }
+}
+
+impl App {
+    fn table_title(&self, visible_count: usize) -> String {
+        let mut title = "Jotforms".to_string();
+        if self.search.is_active() || self.filter.is_active() {
+            title.push_str(&format!(" ({} of {})", visible_count, self.jotforms.len()));
+        }
+        if self.filter.is_active() {
+            title.push_str(&format!(" — {}", self.filter.summary()));
+        }
+        title
+    }
+
+    fn render_filter_panel(&self, area: Rect, buf: &mut Buffer) {
+        let items = filter::items(&self.jotforms, &self.filter);
+        let cursor = self.filter_panel.cursor.min(items.len() - 1);
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+        let heading = dim.add_modifier(Modifier::BOLD);
+
+        let mut lines = Vec::new();
+        let mut cursor_line = 0;
+        let mut last_section = "";
+        for (index, item) in items.iter().enumerate() {
+            let (section, label, color) = match item {
+                FilterItem::Status(s) => ("Status", s.as_str(), s.color()),
+                FilterItem::Priority(p) => ("Priority", p.as_str(), p.color()),
+                FilterItem::Department(d) => ("Department", d.as_str(), d.color()),
+                FilterItem::DateRange => ("Submitted", "", Color::Rgb(200, 200, 200)),
+            };
+            if section != last_section {
+                if !lines.is_empty() {
+                    lines.push(Line::default());
+                }
+                lines.push(Line::styled(section, heading));
+                last_section = section;
+            }
+
+            let line = match item {
+                FilterItem::DateRange if self.filter_panel.editing_date => Line::from(vec![
+                    Span::raw(" > "),
+                    Span::raw(self.filter_panel.date_input.as_str()),
+                    Span::styled("▏", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+                ]),
+                FilterItem::DateRange => Line::from(vec![
+                    Span::raw(" "),
+                    match &self.filter.date_range {
+                        Some(range) => Span::raw(range.input.as_str()),
+                        None => Span::styled("any date", dim),
+                    },
+                ]),
+                _ => {
+                    let checkbox = if self.filter.is_checked(item) {
+                        " [x] "
+                    } else {
+                        " [ ] "
+                    };
+                    Line::from(vec![
+                        Span::raw(checkbox),
+                        Span::styled(label, Style::default().fg(color)),
+                    ])
+                }
+            };
+            if index == cursor {
+                cursor_line = lines.len();
+                lines.push(line.style(Style::default().bg(Color::Rgb(70, 70, 90))));
+            } else {
+                lines.push(line);
+            }
+        }
+
+        if self.filter_panel.editing_date {
+            let error_style = Style::default().fg(Color::Rgb(255, 182, 193));
+            match &self.filter_panel.date_error {
+                Some(error) => lines.push(Line::styled(format!(" {}", error), error_style)),
+                None => lines.push(Line::styled(" 14d, 2024-05-01..2024-05-31", dim)),
+            }
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+            .title("Filters")
+            .title_style(heading)
+            .title_bottom(Line::styled("Space: Toggle  C: Clear", dim))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+        // Keep the cursor line on screen when the list is taller than the panel.
+        let inner_height = area.height.saturating_sub(2) as usize;
+        let scroll = (cursor_line + 1).saturating_sub(inner_height) as u16;
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0))
+            .render(area, buf);
+    }
+
+    fn render_search_bar(&self, match_count: usize, area: Rect, buf: &mut Buffer) {
+        let mut spans = vec![
+            Span::styled("/", highlight_style()),
+            Span::raw(self.search.query.as_str()),
+        ];
+        if self.search.editing {
+            spans.push(Span::styled(
+                "▏",
+                Style::default().add_modifier(Modifier::SLOW_BLINK),
+            ));
+            spans.push(Span::styled(
+                "  Enter: Keep  Esc: Cancel",
+                Style::default().fg(Color::Rgb(150, 150, 170)),
+            ));
+        } else {
+            spans.push(Span::styled(
+                format!("  {} match(es)  n/N: Next/Prev  Esc: Clear", match_count),
+                Style::default().fg(Color::Rgb(150, 150, 170)),
+            ));
+        }
+
+        Paragraph::new(Line::from(spans))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(50, 50, 60))
+                    .fg(Color::Rgb(200, 200, 200)),
+            )
+            .render(area, buf);
+    }
+
+    fn render_status_bar(&self, area: Rect, buf: &mut Buffer) {
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+
+        let mut left = Vec::new();
+        if self.is_busy() {
+            let activity = if self.syncing {
+                format!("Sending {} offline change(s)…", self.outbox.len())
+            } else if self.is_saving() {
+                "Saving…".to_string()
+            } else if self.refreshing {
+                "Refreshing…".to_string()
+            } else {
+                "Loading…".to_string()
+            };
+            left.push(Span::styled(
+                format!(" {} {}", self.spinner(), activity),
+                Style::default().fg(Color::Rgb(216, 191, 216)),
+            ));
+        } else if let Some(notice) = &self.notice {
+            left.push(Span::raw(format!(" {}", notice)));
+        } else if let Some(cached) = self.offline_since {
+            let mut notice = format!(
+                " Offline — showing the copy cached {}",
+                cached.format("%Y-%m-%d %H:%M")
+            );
+            if !self.outbox.is_empty() {
+                notice.push_str(&format!(" · {} change(s) queued", self.outbox.len()));
+            }
+            left.push(Span::styled(
+                notice,
+                Style::default().fg(Color::Rgb(255, 183, 82)),
+            ));
+        } else if let Some(refreshed) = self.last_refreshed {
+            left.push(Span::styled(
+                format!(" Updated {}", refreshed.format("%H:%M:%S")),
+                dim,
+            ));
+        }
+        let right = match self.config.refresh_interval {
+            Some(interval) => format!("Auto-refresh every {}s ", interval.as_secs()),
+            None => "Auto-refresh off ".to_string(),
+        };
+
+        let style = Style::default()
+            .bg(Color::Rgb(50, 50, 60))
+            .fg(Color::Rgb(200, 200, 200));
+        Paragraph::new(Line::from(left))
+            .style(style)
+            .render(area, buf);
+        Paragraph::new(Line::styled(right, dim))
+            .right_aligned()
+            .render(area, buf);
+    }
+
+    /// Modal popups over the main or detail view; errors stay on top.
+    fn render_popups(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(form) = &self.edit_form {
+            self.render_edit_form(form, area, buf);
+        }
+        if let Some(form) = &self.new_ticket {
+            self.render_new_ticket(form, area, buf);
+        }
+        if let Some(input) = &self.note_input {
+            self.render_note_input(input, area, buf);
+        }
+        if let Some(prompt) = &self.export_prompt {
+            self.render_export_prompt(prompt, area, buf);
+        }
+        if self.status_menu.open {
+            self.render_status_menu(area, buf);
+        }
+        if self.assignee_picker.open {
+            self.render_assignee_picker(area, buf);
+        }
+        if let Some(conflict) = self.conflicts.front() {
+            self.render_conflict(conflict, area, buf);
+        }
+        if let Some(error) = self.errors.front() {
+            self.render_error_popup(error, area, buf);
+        }
+    }
+
+    /// Our copy of a ticket next to the server's, field by field.
+    fn render_conflict(&self, conflict: &Conflict, area: Rect, buf: &mut Buffer) {
+        const VALUE_WIDTH: usize = 22;
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+        let value = |field: ConflictField, jotform| {
+            let text = field.value(jotform);
+            let text = if text.chars().count() > VALUE_WIDTH {
+                let cut: String = text.chars().take(VALUE_WIDTH - 1).collect();
+                format!("{}…", cut)
+            } else {
+                text
+            };
+            let text = format!("{:<width$} ", text, width = VALUE_WIDTH);
+            match field {
+                ConflictField::Status => {
+                    Span::styled(text, Style::default().fg(jotform.status.color()))
+                }
+                ConflictField::Priority => {
+                    Span::styled(text, Style::default().fg(jotform.priority_level.color()))
+                }
+                ConflictField::Department => {
+                    Span::styled(text, Style::default().fg(jotform.department.color()))
+                }
+                _ => Span::raw(text),
+            }
+        };
+
+        let mut lines = vec![
+            Line::raw(format!(
+                "{}'s ticket was changed by someone else before your change was saved.",
+                conflict.theirs.submitter_name.first
+            )),
+            Line::default(),
+            Line::styled(
+                format!(
+                    "{:<13}{:<width$} {}",
+                    "",
+                    "Yours",
+                    "Server",
+                    width = VALUE_WIDTH
+                ),
+                dim.add_modifier(Modifier::BOLD),
+            ),
+        ];
+        for field in conflict.fields() {
+            let marker = if conflict.touched(field) { "*" } else { " " };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<11}{} ", field.label(), marker), dim),
+                value(field, &conflict.mine),
+                value(field, &conflict.theirs),
+            ]));
+        }
+        lines.push(Line::default());
+        lines.push(Line::styled(
+            "* your change. Re-apply sends only those fields; keep mine also \
+             overwrites the rest.",
+            dim,
+        ));
+
+        let mut title = format!(" Conflict on ticket {} ", conflict.id());
+        if self.conflicts.len() > 1 {
+            title.push_str(&format!("(1 of {}) ", self.conflicts.len()));
+        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(255, 183, 82)))
+            .title(title)
+            .title_style(
+                Style::default()
+                    .fg(Color::Rgb(255, 183, 82))
+                    .add_modifier(Modifier::BOLD),
+            )
+            .title_bottom(key_hints(&[
+                ("K", "Keep mine"),
+                ("T", "Take theirs"),
+                ("R", "Re-apply"),
+            ]))
+            .padding(Padding::horizontal(1))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+        // The two sentences wrap onto a second line each.
+        let popup = centered(area, 64, lines.len() as u16 + 4);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(popup, buf);
+    }
+
+    fn render_edit_form(&self, form: &EditForm, area: Rect, buf: &mut Buffer) {
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+        let mut lines: Vec<Line> = EditField::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, &field)| {
+                let focused = i == form.focus;
+                let marker = if form.is_dirty(field) { "*" } else { " " };
+                let value = form.value(field);
+                let value = match field {
+                    EditField::Priority => Span::styled(
+                        format!("◂ {} ▸", value),
+                        Style::default().fg(form.priority.color()),
+                    ),
+                    EditField::Department => Span::styled(
+                        format!("◂ {} ▸", value),
+                        Style::default().fg(form.department.color()),
+                    ),
+                    EditField::Location | EditField::Exhibit if focused => {
+                        Span::raw(format!("{}▏", value))
+                    }
+                    EditField::Location | EditField::Exhibit => Span::raw(value.to_string()),
+                };
+                let mut line = Line::from(vec![
+                    Span::styled(format!("{:<11}{} ", field.label(), marker), dim),
+                    value,
+                ]);
+                if focused {
+                    line = line.style(Style::default().bg(Color::Rgb(70, 70, 90)));
+                }
+                line
+            })
+            .collect();
+        if let Some(error) = &form.error {
+            lines.push(Line::default());
+            lines.push(Line::styled(
+                error.as_str(),
+                Style::default().fg(Color::Rgb(255, 182, 193)),
+            ));
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+            .title(format!(" Edit ticket {} ", form.original.id))
+            .title_style(dim.add_modifier(Modifier::BOLD))
+            .title_bottom(key_hints(&[
+                ("Tab", "Next"),
+                ("←/→", "Choose"),
+                ("Enter", "Save"),
+                ("Esc", "Cancel"),
+            ]))
+            .padding(Padding::horizontal(1))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+        let popup = centered(area, 60, lines.len() as u16 + 2);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    fn render_new_ticket(&self, form: &NewTicketForm, area: Rect, buf: &mut Buffer) {
+        const DESCRIPTION_LINES: usize = 6;
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+        let focus_style = Style::default().bg(Color::Rgb(70, 70, 90));
+
+        let mut lines: Vec<Line> = Vec::new();
+        for (i, &field) in TicketField::ALL.iter().enumerate() {
+            let focused = i == form.focus;
+            let label = Span::styled(format!("{:<12}", field.label()), dim);
+            if field == TicketField::Description {
+                lines.push(Line::from(label));
+                // Keep the end of a long description, where the cursor is, in view.
+                let mut text: Vec<&str> = form.description.split('\n').collect();
+                let skip = text.len().saturating_sub(DESCRIPTION_LINES);
+                text.drain(..skip);
+                let last = text.len() - 1;
+                for row in 0..DESCRIPTION_LINES {
+                    let content = match text.get(row) {
+                        Some(line) if focused && row == last => format!("  {}▏", line),
+                        Some(line) => format!("  {}", line),
+                        None => String::new(),
+                    };
+                    let line = Line::from(content);
+                    lines.push(if focused {
+                        line.style(focus_style)
+                    } else {
+                        line
+                    });
+                }
+                continue;
+            }
+
+            let value = match field {
+                TicketField::Priority => Span::styled(
+                    format!("◂ {} ▸", form.priority),
+                    Style::default().fg(form.priority.color()),
+                ),
+                TicketField::Department => Span::styled(
+                    format!("◂ {} ▸", form.department),
+                    Style::default().fg(form.department.color()),
+                ),
+                _ if focused => Span::raw(format!("{}▏", form.value(field))),
+                _ => Span::raw(form.value(field).to_string()),
+            };
+            let line = Line::from(vec![label, value]);
+            lines.push(if focused {
+                line.style(focus_style)
+            } else {
+                line
+            });
+        }
+        if let Some(error) = &form.error {
+            lines.push(Line::styled(
+                error.as_str(),
+                Style::default().fg(Color::Rgb(255, 182, 193)),
+            ));
+        }
+
+        let title = if form.saving {
+            format!(" New ticket — {} Saving… ", self.spinner())
+        } else {
+            " New ticket ".to_string()
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+            .title(title)
+            .title_style(dim.add_modifier(Modifier::BOLD))
+            .title_bottom(key_hints(&[
+                ("Tab", "Next"),
+                ("←/→", "Choose"),
+                ("Ctrl-S", "Submit"),
+                ("Esc", "Cancel"),
+            ]))
+            .padding(Padding::horizontal(1))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+        let popup = centered(area, 70, lines.len() as u16 + 2);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    fn render_assignee_picker(&self, area: Rect, buf: &mut Buffer) {
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+        let current = self
+            .jotforms
+            .iter()
+            .find(|j| j.id == self.selected_id)
+            .and_then(|j| j.assignee.as_ref());
+
+        let lines: Vec<Line> = match &self.assignee_picker.choices {
+            None => vec![Line::styled(
+                format!("{} Loading staff…", self.spinner()),
+                dim,
+            )],
+            Some(choices) => choices
+                .iter()
+                .enumerate()
+                .map(|(i, choice)| {
+                    let marker = if choice.as_ref() == current {
+                        "● "
+                    } else {
+                        "  "
+                    };
+                    let name = match choice {
+                        Some(name) if Some(name) == self.config.user.as_ref() => {
+                            Span::raw(format!("{} (me)", name))
+                        }
+                        Some(name) => Span::raw(name.clone()),
+                        None => Span::styled("Unassigned", dim),
+                    };
+                    let line = Line::from(vec![Span::raw(marker), name]);
+                    if i == self.assignee_picker.cursor {
+                        line.style(Style::default().bg(Color::Rgb(70, 70, 90)))
+                    } else {
+                        line
+                    }
+                })
+                .collect(),
+        };
+
+        let mut hints = vec![("↑/↓", "Move"), ("Enter", "Assign")];
+        if self.config.user.is_some() {
+            hints.push(("M", "Me"));
+        }
+        hints.extend([("U", "Unassign"), ("Esc", "Cancel")]);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+            .title(format!(" Assign ticket {} ", self.selected_id))
+            .title_style(dim.add_modifier(Modifier::BOLD))
+            .title_bottom(key_hints(&hints))
+            .padding(Padding::horizontal(1))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+
+        // Keep the cursor in view on a long staff list.
+        let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+        let visible = height.saturating_sub(2) as usize;
+        let scroll = (self.assignee_picker.cursor + 1).saturating_sub(visible) as u16;
+        let popup = centered(area, 56, height);
+        Clear.render(popup, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((scroll, 0))
+            .render(popup, buf);
+    }
+
+    fn render_status_menu(&self, area: Rect, buf: &mut Buffer) {
+        let Some(jotform) = self.jotforms.iter().find(|j| j.id == self.selected_id) else {
+            return;
+        };
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+            .title(format!(" Status of {} ", jotform.id))
+            .title_style(dim.add_modifier(Modifier::BOLD))
+            .padding(Padding::horizontal(1))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+
+        if let Some(target) = &self.status_menu.confirming {
+            let text = vec![
+                Line::from(vec![
+                    Span::raw("Close "),
+                    Span::styled(
+                        jotform.priority_level.as_str(),
+                        Style::default().fg(jotform.priority_level.color()),
+                    ),
+                    Span::raw("-priority ticket?"),
+                ]),
+                Line::from(vec![
+                    Span::styled(
+                        jotform.status.as_str(),
+                        Style::default().fg(jotform.status.color()),
+                    ),
+                    Span::raw(" → "),
+                    Span::styled(target.as_str(), Style::default().fg(target.color())),
+                ]),
+            ];
+            let popup = centered(area, 44, 4);
+            Clear.render(popup, buf);
+            Paragraph::new(text)
+                .block(block.title_bottom(key_hints(&[("Y", "Close it"), ("N", "Back")])))
+                .render(popup, buf);
+            return;
+        }
+
+        let rows: Vec<Line> = Status::KNOWN
+            .iter()
+            .enumerate()
+            .map(|(i, status)| {
+                let marker = if *status == jotform.status {
+                    "● "
+                } else {
+                    "  "
+                };
+                let mut line = Line::from(vec![
+                    Span::styled(format!("{} ", i + 1), dim),
+                    Span::raw(marker),
+                    Span::styled(status.as_str(), Style::default().fg(status.color())),
+                ]);
+                if i == self.status_menu.cursor {
+                    line = line.style(Style::default().bg(Color::Rgb(70, 70, 90)));
+                }
+                line
+            })
+            .collect();
+        let pick = format!("1-{}", Status::KNOWN.len());
+        let popup = centered(area, 44, rows.len() as u16 + 2);
+        Clear.render(popup, buf);
+        Paragraph::new(rows)
+            .block(block.title_bottom(key_hints(&[
+                ("↑/↓", "Move"),
+                ("Enter", "Set"),
+                (&pick, "Pick"),
+                ("Esc", "Cancel"),
+            ])))
+            .render(popup, buf);
+    }
+
+    fn render_error_popup(&self, error: &ErrorPopup, area: Rect, buf: &mut Buffer) {
+        let width = (area.width * 3 / 5).clamp(30.min(area.width), area.width);
+        // Rough wrapped height: borders, padding and the hint line.
+        let text_width = width.saturating_sub(4).max(1) as usize;
+        let text_lines: usize = error
+            .message
+            .lines()
+            .map(|line| line.chars().count().div_ceil(text_width).max(1))
+            .sum();
+        let height = (text_lines as u16 + 4).min(area.height);
+        let popup = centered(area, width, height);
+
+        let mut hint = "Enter/Esc: Dismiss".to_string();
+        if self.errors.len() > 1 {
+            hint.push_str(&format!("  (1 of {})", self.errors.len()));
+        }
+        let red = Color::Rgb(255, 182, 193);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(red))
+            .title(format!(" {} ", error.title))
+            .title_style(Style::default().fg(red).add_modifier(Modifier::BOLD))
+            .title_bottom(Line::styled(
+                hint,
+                Style::default().fg(Color::Rgb(150, 150, 170)),
+            ))
+            .padding(Padding::horizontal(1))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(40, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+
+        Clear.render(popup, buf);
+        Paragraph::new(error.message.as_str())
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(popup, buf);
+    }
+
+    /// Wrapped, scrollable description with search matches highlighted.
+    fn render_description(&self, description: &str, focused: bool, area: Rect, buf: &mut Buffer) {
+        let description_block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(pane_border(focused))
+            .title("Description")
+            .title_style(
+                Style::default()
+                    .fg(Color::Rgb(150, 150, 170))
+                    .add_modifier(Modifier::BOLD),
+            )
+            .padding(Padding::new(1, 1, 1, 1))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+
+        let description_text = Text::from(
+            description
+                .lines()
+                .map(|line| self.search.highlight(line, Style::default()))
+                .collect::<Vec<_>>(),
+        );
+        let desc_paragraph = Paragraph::new(description_text)
+            .block(description_block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.description_offset, 0));
+
+        desc_paragraph.render(area, buf);
+
+        let total_lines = description.lines().count();
+        let visible_lines = area.height.saturating_sub(2) as usize;
+
+        let scroll_state = self
+            .scroll_state
+            .content_length(total_lines)
+            .viewport_content_length(visible_lines)
+            .position(self.description_offset as usize);
+
+        let scrollbar = Scrollbar::default()
+            .orientation(ratatui::widgets::ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"));
+        scrollbar.render(area, buf, &mut scroll_state.clone());
+    }
+
+    /// Full-screen view of every field of the selected jotform.
+    fn render_detail(&self, area: Rect, buf: &mut Buffer) {
+        let Some(jotform) = self.jotforms.iter().find(|j| j.id == self.selected_id) else {
+            Paragraph::new("The selected jotform is no longer available. Press Esc to go back.")
+                .render(area, buf);
+            return;
+        };
+
+        let label = Style::default()
+            .fg(Color::Rgb(150, 150, 170))
+            .add_modifier(Modifier::BOLD);
+        let field = |name: &'static str, value: Span<'static>| {
+            Line::from(vec![Span::styled(format!("{:<12}", name), label), value])
+        };
+        let plain = |value: &str| Span::raw(value.to_string());
+        let colored =
+            |value: &str, color: Color| Span::styled(value.to_string(), Style::default().fg(color));
+
+        let submitted = jotform
+            .created_at
+            .timestamp()
+            .map(|at| at.format("%m-%d-%Y %H:%M").to_string())
+            .unwrap_or_else(|| format!("{} {}", jotform.created_at.date, jotform.created_at.time));
+        let age = jotform
+            .created_at
+            .relative_age(chrono::Local::now().naive_local())
+            .map(|age| format!("  ({})", age))
+            .unwrap_or_default();
+
+        let fields = vec![
+            field("ID", plain(&jotform.id)),
+            field(
+                "Status",
+                colored(jotform.status.as_str(), jotform.status.color()),
+            ),
+            field(
+                "Priority",
+                colored(
+                    jotform.priority_level.as_str(),
+                    jotform.priority_level.color(),
+                ),
+            ),
+            field(
+                "Department",
+                colored(jotform.department.as_str(), jotform.department.color()),
+            ),
+            field(
+                "Submitter",
+                plain(&format!(
+                    "{} {}",
+                    jotform.submitter_name.first, jotform.submitter_name.last
+                )),
+            ),
+            field("Submitted", plain(&format!("{}{}", submitted, age))),
+            field("Location", plain(&jotform.location)),
+            field("Exhibit", plain(&jotform.exhibit_name)),
+            field(
+                "Assignee",
+                plain(jotform.assignee.as_deref().unwrap_or("Unassigned")),
+            ),
+        ];
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+            .title(format!("Jotform {}", jotform.id))
+            .title_style(label)
+            .title_bottom(key_hints(&[
+                ("Esc", "Back"),
+                ("↑/↓", "Scroll"),
+                ("Tab", "Pane"),
+                ("←/→", "Prev/Next"),
+                ("E", "Status"),
+                ("C", "Edit"),
+                ("A", "Assign"),
+                ("W", "Note"),
+                ("P", "Print"),
+                ("Q", "Quit"),
+            ]))
+            .padding(Padding::new(1, 1, 1, 0))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(fields.len() as u16 + 1),
+                Constraint::Percentage(40),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+        Paragraph::new(fields).render(chunks[0], buf);
+        self.render_description(
+            &jotform.description,
+            self.detail_pane == DetailPane::Description,
+            chunks[1],
+            buf,
+        );
+        self.render_notes(&jotform.id, chunks[2], buf);
+    }
+
+    /// The ticket's work log, oldest first, under the description.
+    fn render_notes(&self, id: &str, area: Rect, buf: &mut Buffer) {
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+        let thread = self.notes.get(id);
+        let count = thread.map_or(0, |thread| thread.notes.len());
+
+        let mut lines = Vec::new();
+        match thread {
+            Some(thread) if thread.loading && thread.notes.is_empty() => {
+                lines.push(Line::styled(
+                    format!("{} Loading notes…", self.spinner()),
+                    dim,
+                ));
+            }
+            Some(thread) => {
+                if let Some(error) = &thread.error {
+                    lines.push(Line::styled(
+                        error.as_str(),
+                        Style::default().fg(Color::Rgb(255, 182, 193)),
+                    ));
+                }
+                let now = chrono::Local::now().naive_local();
+                for note in &thread.notes {
+                    let stamp = note
+                        .created_at
+                        .timestamp()
+                        .map(|at| at.format("%m-%d-%Y %H:%M").to_string())
+                        .unwrap_or_else(|| note.created_at.date.clone());
+                    let age = note
+                        .created_at
+                        .relative_age(now)
+                        .map(|age| format!(" · {}", age))
+                        .unwrap_or_default();
+                    lines.push(Line::styled(
+                        format!("{}{}", stamp, age),
+                        dim.add_modifier(Modifier::BOLD),
+                    ));
+                    lines.extend(note.text.lines().map(|line| Line::from(line.to_string())));
+                    lines.push(Line::default());
+                }
+                if thread.notes.is_empty() && thread.error.is_none() {
+                    lines.push(Line::styled("No notes yet. Press w to add one.", dim));
+                }
+            }
+            None => {}
+        }
+
+        let total_lines = lines.len();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(pane_border(self.detail_pane == DetailPane::Notes))
+            .title(format!("Notes ({})", count))
+            .title_style(dim.add_modifier(Modifier::BOLD))
+            .padding(Padding::horizontal(1))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((self.notes_offset, 0))
+            .render(area, buf);
+
+        let mut scroll_state = ScrollbarState::new(total_lines)
+            .viewport_content_length(area.height.saturating_sub(2) as usize)
+            .position(self.notes_offset as usize);
+        Scrollbar::default()
+            .orientation(ratatui::widgets::ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"))
+            .render(area, buf, &mut scroll_state);
+    }
+
+    fn render_note_input(&self, input: &NoteInput, area: Rect, buf: &mut Buffer) {
+        const INPUT_LINES: usize = 6;
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+
+        // Keep the end of the note, where the cursor is, in view.
+        let mut text: Vec<&str> = input.text.split('\n').collect();
+        let skip = text.len().saturating_sub(INPUT_LINES);
+        text.drain(..skip);
+        let last = text.len() - 1;
+        let mut lines: Vec<Line> = (0..INPUT_LINES)
+            .map(|row| match text.get(row) {
+                Some(line) if row == last => Line::from(format!("{}▏", line)),
+                Some(line) => Line::from(line.to_string()),
+                None => Line::default(),
+            })
+            .collect();
+        if let Some(error) = &input.error {
+            lines.push(Line::styled(
+                error.as_str(),
+                Style::default().fg(Color::Rgb(255, 182, 193)),
+            ));
+        }
+
+        let title = if input.saving {
+            format!(
+                " Note for ticket {} — {} Saving… ",
+                input.id,
+                self.spinner()
+            )
+        } else {
+            format!(" Note for ticket {} ", input.id)
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+            .title(title)
+            .title_style(dim.add_modifier(Modifier::BOLD))
+            .title_bottom(key_hints(&[
+                ("Enter", "New line"),
+                ("Ctrl-S", "Add"),
+                ("Esc", "Cancel"),
+            ]))
+            .padding(Padding::horizontal(1))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+        let popup = centered(area, 70, lines.len() as u16 + 2);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    fn render_export_prompt(&self, prompt: &ExportPrompt, area: Rect, buf: &mut Buffer) {
+        const WIDTH: u16 = 64;
+        const LABEL: usize = 8;
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+
+        let mut formats = vec![Span::styled(format!("{:<LABEL$}", "Format"), dim)];
+        for format in Format::ALL {
+            let style = if format == prompt.format {
+                Style::default()
+                    .bg(Color::Rgb(70, 70, 90))
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                dim
+            };
+            formats.push(Span::styled(format!(" {} ", format.label()), style));
+            formats.push(Span::raw(" "));
+        }
+        // Keep the end of a long path, where the cursor is, in view.
+        let room = usize::from(WIDTH) - 4 - LABEL - 1;
+        let path: String = {
+            let chars: Vec<char> = prompt.path.chars().collect();
+            chars[chars.len().saturating_sub(room)..].iter().collect()
+        };
+        let mut lines = vec![
+            Line::from(formats),
+            Line::default(),
+            Line::from(vec![
+                Span::styled(format!("{:<LABEL$}", "File"), dim),
+                Span::raw(format!("{}▏", path)),
+            ]),
+        ];
+        if let Some(error) = &prompt.error {
+            lines.push(Line::styled(
+                error.as_str(),
+                Style::default().fg(Color::Rgb(255, 182, 193)),
+            ));
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+            .title(format!(
+                " Export {} ticket(s) ",
+                self.visible_jotforms().len()
+            ))
+            .title_style(dim.add_modifier(Modifier::BOLD))
+            .title_bottom(key_hints(&[
+                ("Tab", "Format"),
+                ("Enter", "Save"),
+                ("Esc", "Cancel"),
+            ]))
+            .padding(Padding::horizontal(1))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+        let popup = centered(area, WIDTH, lines.len() as u16 + 2);
+        Clear.render(popup, buf);
+        Paragraph::new(lines).block(block).render(popup, buf);
+    }
+
+    /// Workload at a glance, from every loaded ticket regardless of the
+    /// table's filter and search.
+    fn render_dashboard(&self, area: Rect, buf: &mut Buffer) {
+        let stats = Stats::new(&self.jotforms, chrono::Local::now().naive_local());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+            .title(format!("Dashboard — {} tickets", self.jotforms.len()))
+            .title_style(
+                Style::default()
+                    .fg(Color::Rgb(150, 150, 170))
+                    .add_modifier(Modifier::BOLD),
+            )
+            .title_bottom(key_hints(&[
+                ("Tab", "Tickets"),
+                ("R", "Refresh"),
+                ("Q", "Quit"),
+            ]))
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            );
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let count_rows = [
+            stats.by_status.len(),
+            stats.by_priority.len(),
+            stats.by_department.len(),
+            stats.open_by_age.len(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0) as u16;
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(count_rows + 2),
+                Constraint::Length(dashboard::TOP as u16 + 2),
+                Constraint::Min(5),
+            ])
+            .split(inner);
+
+        let quarters = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 4); 4])
+            .split(rows[0]);
+        count_chart(
+            "By status",
+            stats
+                .by_status
+                .iter()
+                .map(|(status, n)| (status.as_str(), *n, status.color()))
+                .collect(),
+            quarters[0],
+            buf,
+        );
+        count_chart(
+            "By priority",
+            stats
+                .by_priority
+                .iter()
+                .map(|(priority, n)| (priority.as_str(), *n, priority.color()))
+                .collect(),
+            quarters[1],
+            buf,
+        );
+        count_chart(
+            "By department",
+            stats
+                .by_department
+                .iter()
+                .map(|(department, n)| (department.as_str(), *n, department.color()))
+                .collect(),
+            quarters[2],
+            buf,
+        );
+        count_chart(
+            "Open by age",
+            stats
+                .open_by_age
+                .iter()
+                .map(|&(bucket, n)| (bucket, n, Color::Rgb(216, 191, 216)))
+                .collect(),
+            quarters[3],
+            buf,
+        );
+
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 2); 2])
+            .split(rows[1]);
+        top_table("Top locations", &stats.top_locations, halves[0], buf);
+        top_table("Top exhibits", &stats.top_exhibits, halves[1], buf);
+
+        self.render_submissions_chart(&stats, rows[2], buf);
+    }
+
+    /// One bar per day, as many of the latest days as fit.
+    fn render_submissions_chart(&self, stats: &Stats, area: Rect, buf: &mut Buffer) {
+        let total: u64 = stats.per_day.iter().map(|(_, n)| n).sum();
+        let block = panel(format!(
+            "Submissions per day, last {} days ({})",
+            dashboard::DAYS,
+            total
+        ));
+        let width = block.inner(area).width;
+        let days = stats.per_day.len() as u16;
+        // Widest bars that fit every day, one column apart.
+        let bar_width = ((width + 1) / days.max(1)).saturating_sub(1).clamp(1, 4);
+        let gap = u16::from(bar_width > 1);
+        let shown = usize::from((width + gap) / (bar_width + gap));
+        let skip = stats.per_day.len().saturating_sub(shown);
+
+        let color = Color::Rgb(173, 216, 230);
+        let bars: Vec<Bar> = stats.per_day[skip..]
+            .iter()
+            .map(|(day, n)| {
+                let mut bar = Bar::default()
+                    .value(*n)
+                    .style(Style::default().fg(color))
+                    .value_style(Style::default().fg(Color::Rgb(30, 30, 40)).bg(color));
+                if *n == 0 {
+                    bar = bar.text_value(String::new());
+                }
+                if bar_width > 1 {
+                    bar = bar.label(Line::from(day.format("%d").to_string()));
+                }
+                bar
+            })
+            .collect();
+        BarChart::default()
+            .block(block)
+            .bar_width(bar_width)
+            .bar_gap(gap)
+            .label_style(Style::default().fg(Color::Rgb(150, 150, 170)))
+            .data(BarGroup::default().bars(&bars))
+            .render(area, buf);
+    }
+
+    fn spinner(&self) -> &'static str {
+        const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+        SPINNER[self.spinner_frame % SPINNER.len()]
+    }
+
+    /// Full-screen view shown until the first list arrives: a spinner while
+    /// connecting, or the failing URL and error with recovery options.
+    fn render_startup(&self, area: Rect, buf: &mut Buffer) {
+        let dim = Style::default().fg(Color::Rgb(150, 150, 170));
+        let label = Style::default()
+            .fg(Color::Rgb(150, 150, 170))
+            .add_modifier(Modifier::BOLD);
+        let key = Style::default()
+            .fg(Color::Rgb(200, 200, 200))
+            .add_modifier(Modifier::BOLD);
+        let red = Color::Rgb(255, 182, 193);
+        let url = self.backend.location();
+
+        let (title, border, lines) = match &self.startup {
+            Startup::Failed(failure) => {
+                let retry = match failure.retry_at {
+                    Some(at) => {
+                        let secs = at.saturating_duration_since(Instant::now()).as_secs() + 1;
+                        format!("Retrying in {}s (attempt {})", secs, failure.attempt + 1)
+                    }
+                    None => format!("Failed after {} attempt(s)", failure.attempt),
+                };
+                let offline = match failure.offline_copy {
+                    Some((saved_at, count)) => format!(
+                        "Start offline with {} cached jotforms from {}",
+                        count,
+                        saved_at.format("%Y-%m-%d %H:%M")
+                    ),
+                    None => "Start offline (no cached copy)".to_string(),
+                };
+                let lines = vec![
+                    Line::from(vec![
+                        Span::styled("URL    ", label),
+                        Span::raw(failure.url.as_str()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled("Error  ", label),
+                        Span::styled(failure.error.as_str(), Style::default().fg(red)),
+                    ]),
+                    Line::default(),
+                    Line::styled(retry, dim),
+                    Line::default(),
+                    Line::from(vec![Span::styled("R  ", key), Span::raw("Retry")]),
+                    Line::from(vec![Span::styled("C  ", key), Span::raw("Edit config")]),
+                    Line::from(vec![Span::styled("O  ", key), Span::raw(offline)]),
+                    Line::from(vec![Span::styled("Q  ", key), Span::raw("Quit")]),
+                ];
+                (" Could not connect to the jotforms backend ", red, lines)
+            }
+            Startup::Connecting { attempt, .. } => {
+                let mut lines = vec![Line::from(vec![
+                    Span::styled(
+                        format!("{} ", self.spinner()),
+                        Style::default().fg(Color::Rgb(216, 191, 216)),
+                    ),
+                    Span::raw(format!("Loading {}", url)),
+                ])];
+                if *attempt > 1 {
+                    lines.push(Line::styled(format!("Attempt {}", attempt), dim));
+                }
+                lines.push(Line::default());
+                lines.push(Line::from(vec![
+                    Span::styled("Q  ", key),
+                    Span::raw("Quit"),
+                ]));
+                (" Connecting ", Color::Rgb(100, 100, 120), lines)
+            }
+            Startup::Done => return,
+        };
+
+        let height = (lines.len() as u16 + 4).min(area.height);
+        let width = (area.width * 4 / 5).clamp(40.min(area.width), area.width);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border))
+            .title(title)
+            .title_style(Style::default().fg(border).add_modifier(Modifier::BOLD))
+            .padding(Padding::new(2, 2, 1, 1));
+
+        Block::default()
+            .style(
+                Style::default()
+                    .bg(Color::Rgb(30, 30, 40))
+                    .fg(Color::Rgb(200, 200, 200)),
+            )
+            .render(area, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(centered(area, width, height), buf);
+    }
+}
+
+/// A row of `key: action` hints for a block's bottom border.
+fn key_hints<'a>(hints: &[(&'a str, &'a str)]) -> Line<'a> {
+    let key = Style::default()
+        .fg(Color::Rgb(200, 200, 200))
+        .add_modifier(Modifier::BOLD);
+    let action = Style::default().fg(Color::Rgb(150, 150, 170));
+    let mut spans = vec![Span::raw(" ")];
+    for (k, a) in hints {
+        spans.push(Span::styled(*k, key));
+        spans.push(Span::styled(format!(" {}  ", a), action));
+    }
+    Line::from(spans)
+}
+
+/// A titled box inside the dashboard.
+fn panel<'a>(title: String) -> Block<'a> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Rgb(100, 100, 120)))
+        .title(format!(" {} ", title))
+        .title_style(Style::default().fg(Color::Rgb(150, 150, 170)))
+        .padding(Padding::horizontal(1))
+}
+
+/// Labelled horizontal bars, one per row, in the given colors.
+fn count_chart(title: &str, counts: Vec<(&str, u64, Color)>, area: Rect, buf: &mut Buffer) {
+    let block = panel(title.to_string());
+    let label_width = counts
+        .iter()
+        .map(|(label, _, _)| label.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    // Too narrow for labels and bars both; the chart would panic.
+    if block.inner(area).width <= label_width + 1 {
+        block.render(area, buf);
+        return;
+    }
+    let bars: Vec<Bar> = counts
+        .into_iter()
+        .map(|(label, n, color)| {
+            Bar::default()
+                .label(Line::from(label.to_string()))
+                .value(n)
+                .style(Style::default().fg(color))
+                .value_style(Style::default().fg(Color::Rgb(30, 30, 40)).bg(color))
+        })
+        .collect();
+    BarChart::default()
+        .block(block)
+        .direction(Direction::Horizontal)
+        .bar_width(1)
+        .bar_gap(0)
+        .label_style(Style::default().fg(Color::Rgb(200, 200, 200)))
+        .data(BarGroup::default().bars(&bars))
+        .render(area, buf);
+}
+
+/// Names with their ticket counts, most first.
+fn top_table(title: &str, counts: &[(String, u64)], area: Rect, buf: &mut Buffer) {
+    let block = panel(title.to_string());
+    if counts.is_empty() {
+        Paragraph::new(Line::styled(
+            "No tickets",
+            Style::default().fg(Color::Rgb(150, 150, 170)),
+        ))
+        .block(block)
+        .render(area, buf);
+        return;
+    }
+    let rows = counts.iter().map(|(name, n)| {
+        Row::new([
+            Cell::from(name.as_str()),
+            Cell::from(Line::from(n.to_string()).right_aligned()),
+        ])
+    });
+    Widget::render(
+        Table::new(rows, [Constraint::Min(0), Constraint::Length(5)]).block(block),
+        area,
+        buf,
+    );
+}
+
+/// Brighter borders mark the pane the scroll keys apply to.
+fn pane_border(focused: bool) -> Style {
+    if focused {
+        Style::default().fg(Color::Rgb(150, 150, 170))
+    } else {
+        Style::default().fg(Color::Rgb(100, 100, 120))
+    }
+}
+
+/// A `width` × `height` rectangle centred in `area`.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::MemoryBackend,
+        config::Config,
+        model::{Department, Jotform, Priority},
+        test_support::{jotform, name, submitted},
+    };
+    use std::{env, fs, path::PathBuf, sync::Arc};
+
+    /// Every column differs between rows, so the snapshots show each one.
+    fn jotforms() -> Vec<Jotform> {
+        let rows = [
+            (
+                "1",
+                "Ann",
+                "Lobby",
+                "Dinosaurs",
+                Priority::Medium,
+                Department::Operations,
+                Status::Open,
+            ),
+            (
+                "2",
+                "Bo",
+                "East Wing",
+                "Ocean Life",
+                Priority::High,
+                Department::Exhibits,
+                Status::InProgress,
+            ),
+            (
+                "3",
+                "Cy",
+                "Gallery 2",
+                "Egypt",
+                Priority::Low,
+                Department::Exhibits,
+                Status::Closed,
+            ),
+            (
+                "4",
+                "Dee",
+                "Atrium",
+                "Space",
+                Priority::High,
+                Department::Operations,
+                Status::Unplanned,
+            ),
+        ];
+        let mut jotforms: Vec<Jotform> = rows
+            .into_iter()
+            .map(
+                |(id, first, location, exhibit, priority, department, status)| Jotform {
+                    submitter_name: name(first),
+                    created_at: submitted(&format!("2024-05-0{}", id)),
+                    location: location.to_string(),
+                    exhibit_name: exhibit.to_string(),
+                    description: format!("Ticket {} needs looking at.", id),
+                    priority_level: priority,
+                    department,
+                    status,
+                    ..jotform(id)
+                },
+            )
+            .collect();
+        jotforms[0].assignee = Some("Bo Smith".to_string());
+        jotforms[1].description = (1..=8)
+            .map(|n| format!("Line {}", n))
+            .collect::<Vec<_>>()
+            .join("\n");
+        jotforms
+    }
+
+    /// A loaded app with nothing time-dependent on screen.
+    fn app() -> App {
+        let mut app = App::new(Config::in_memory(), Arc::new(MemoryBackend::default()));
+        app.jotforms = jotforms();
+        app.sort.apply(&mut app.jotforms);
+        app.selected_id = app.jotforms[0].id.clone();
+        app.startup = Startup::Done;
+        app
+    }
+
+    fn render(app: &App, width: u16, height: u16) -> Buffer {
+        let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+        app.render(buf.area, &mut buf);
+        buf
+    }
+
+    /// The buffer's text, one line per row, without trailing blanks.
+    fn lines(buf: &Buffer) -> Vec<String> {
+        (0..buf.area.height)
+            .map(|y| {
+                let line: String = (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect();
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// Position of the first cell of `needle`; the fixtures are ASCII, so
+    /// characters and cells line up.
+    fn find(buf: &Buffer, needle: &str) -> (u16, u16) {
+        lines(buf)
+            .iter()
+            .enumerate()
+            .find_map(|(y, line)| {
+                let x = line.find(needle)?;
+                Some((line[..x].chars().count() as u16, y as u16))
+            })
+            .unwrap_or_else(|| panic!("{:?} is not on screen", needle))
+    }
+
+    /// Compares the rendering with `src/snapshots/{name}.txt`. On a mismatch
+    /// the new rendering is written beside it as `{name}.txt.new` for review;
+    /// run with `UPDATE_SNAPSHOTS=1` to accept it.
+    fn assert_snapshot(name: &str, buf: &Buffer) {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/snapshots");
+        let golden = dir.join(format!("{}.txt", name));
+        let pending = dir.join(format!("{}.txt.new", name));
+        let actual = lines(buf).join("\n") + "\n";
+
+        if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(&golden, &actual).unwrap();
+            let _ = fs::remove_file(&pending);
+            return;
+        }
+        let expected = fs::read_to_string(&golden).unwrap_or_default();
+        if expected == actual {
+            let _ = fs::remove_file(&pending);
+            return;
+        }
+        fs::write(&pending, &actual).unwrap();
+
+        let expected: Vec<&str> = expected.lines().collect();
+        let actual: Vec<&str> = actual.lines().collect();
+        let mut diff = String::new();
+        for row in 0..expected.len().max(actual.len()) {
+            let (old, new) = (expected.get(row), actual.get(row));
+            if old != new {
+                diff.push_str(&format!("{:>3} - {}\n", row, old.unwrap_or(&"")));
+                diff.push_str(&format!("{:>3} + {}\n", row, new.unwrap_or(&"")));
+            }
+        }
+        panic!(
+            "{} differs from its snapshot; review {} and rerun with UPDATE_SNAPSHOTS=1 to accept\n{}",
+            name,
+            pending.display(),
+            diff
+        );
+    }
+
+    #[test]
+    fn main_view_matches_snapshots_at_several_sizes() {
+        let app = app();
+        for (width, height) in [(120, 32), (80, 24), (60, 16)] {
+            let buf = render(&app, width, height);
+            assert_snapshot(&format!("main_{}x{}", width, height), &buf);
+
+            let lines = lines(&buf);
+            assert!(lines[1].starts_with("│Sub"), "header: {:?}", lines[1]);
+            assert!(
+                lines[height as usize - 1].ends_with("Auto-refresh off"),
+                "footer: {:?}",
+                lines[height as usize - 1]
+            );
+        }
+    }
+
+    #[test]
+    fn rows_show_each_ticket_with_the_selection_and_field_colors() {
+        let app = app();
+        let buf = render(&app, 120, 32);
+
+        let header = lines(&buf)[1].clone();
+        assert!(header.contains("Status ▲"), "{:?}", header);
+        for first in ["Ann", "Bo", "Cy", "Dee"] {
+            find(&buf, first);
+        }
+
+        // In-progress work sorts first and starts selected.
+        let (x, selected) = find(&buf, "Bo ");
+        assert_eq!(selected, 2);
+        assert_eq!(buf[(x, selected)].bg, Color::Rgb(70, 70, 90));
+        let (x, other) = find(&buf, "Ann");
+        assert_eq!(buf[(x, other)].bg, Color::Rgb(30, 30, 40));
+
+        for (text, color) in [
+            ("InProgress", Status::InProgress.color()),
+            ("Unplanned", Status::Unplanned.color()),
+            ("High", Priority::High.color()),
+            ("Medium", Priority::Medium.color()),
+            ("Exhibits", Department::Exhibits.color()),
+            ("Operations", Department::Operations.color()),
+        ] {
+            let (x, y) = find(&buf, text);
+            assert_eq!(buf[(x, y)].fg, color, "{}", text);
+        }
+        let (x, y) = find(&buf, "Bo Smith");
+        assert_eq!(buf[(x, y)].fg, Color::Reset);
+    }
+
+    #[test]
+    fn description_scrolls_by_its_offset() {
+        let mut app = app();
+        let buf = render(&app, 80, 24);
+        let (_, first) = find(&buf, "Line 1");
+        assert!(!lines(&buf).iter().any(|line| line.contains("Line 6")));
+
+        app.description_offset = 3;
+        let buf = render(&app, 80, 24);
+        assert_snapshot("description_scrolled_80x24", &buf);
+        assert_eq!(find(&buf, "Line 4").1, first);
+        assert!(!lines(&buf).iter().any(|line| line.contains("Line 1")));
+    }
+
+    #[test]
+    fn dashboard_counts_every_loaded_ticket() {
+        let mut app = app();
+        let today = chrono::Local::now().date_naive();
+        app.jotforms[0].created_at.date = today.format("%Y-%m-%d").to_string();
+        app.search.query = "nothing matches this".to_string();
+        app.dashboard_open = true;
+
+        let buf = render(&app, 100, 30);
+        find(&buf, "Dashboard — 4 tickets");
+        find(&buf, "High   2");
+        find(&buf, "Under a day 1");
+        find(&buf, "Lobby ");
+        find(&buf, "Submissions per day, last 30 days (1)");
+        // Today's bar is the one above today's label.
+        let rows = lines(&buf);
+        let labels_y = rows.len() - 4;
+        let label = format!(" {}", today.format("%d"));
+        let at = rows[labels_y].rfind(&label).unwrap() + 1;
+        let x = rows[labels_y][..at].chars().count() as u16;
+        assert_eq!(buf[(x, labels_y as u16 - 1)].symbol(), "1");
+
+        // Too small to be useful, but it mustn't panic.
+        render(&app, 30, 10);
+    }
+}